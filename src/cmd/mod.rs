@@ -1,7 +1,7 @@
 pub mod errors;
 
 use self::errors::Error;
-use std::process::Command;
+use std::{path::Path, process::Command};
 
 /// Execute a given command and return its
 /// Stdout output as string.
@@ -32,3 +32,72 @@ pub fn exec(cmd: &[&str]) -> Result<String, Error> {
     let stdout = std::str::from_utf8(&res.stdout)?;
     Ok(stdout.to_string())
 }
+
+/// Execute a given command with the given additional
+/// environment variables set and return its Stdout output
+/// as string.
+///
+/// # Errors
+/// If the given command has a non-zero status
+/// code, an [`Error`] of [`ErrorKind::Status`]
+/// is returned.
+///
+/// # Example
+/// ```
+/// let output = exec_with_env(&["go", "version"], &[("GOTOOLCHAIN", "local")]).unwrap();
+/// ```
+pub fn exec_with_env(cmd: &[&str], envs: &[(&str, &str)]) -> Result<String, Error> {
+    if cmd.is_empty() {
+        return Err(Error::Parameters("command is empty".into()));
+    }
+
+    let res = Command::new(cmd[0])
+        .args(&cmd[1..])
+        .envs(envs.iter().copied())
+        .output()?;
+
+    if !res.status.success() {
+        let stderr = std::str::from_utf8(&res.stderr)?;
+        return Err(Error::Status(res.status, stderr.into()));
+    }
+
+    let stdout = std::str::from_utf8(&res.stdout)?;
+    Ok(stdout.to_string())
+}
+
+/// Execute a given command in the given working directory
+/// with the given additional environment variables set.
+///
+/// In contrast to [`exec`], Stdout and Stderr are passed
+/// through to the current terminal, which is useful for
+/// long running commands like builds.
+///
+/// # Errors
+/// If the given command has a non-zero status
+/// code, an [`Error`] of [`ErrorKind::Status`]
+/// is returned.
+///
+/// # Example
+/// ```
+/// exec_attached(&["./make.bash"], Some(Path::new("go/src")), &[]).unwrap();
+/// ```
+pub fn exec_attached(cmd: &[&str], dir: Option<&Path>, envs: &[(&str, &str)]) -> Result<(), Error> {
+    if cmd.is_empty() {
+        return Err(Error::Parameters("command is empty".into()));
+    }
+
+    let mut command = Command::new(cmd[0]);
+    command.args(&cmd[1..]).envs(envs.iter().copied());
+
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+
+    let status = command.status()?;
+
+    if !status.success() {
+        return Err(Error::Status(status, String::new()));
+    }
+
+    Ok(())
+}
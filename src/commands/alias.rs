@@ -0,0 +1,122 @@
+use super::Command;
+use crate::{
+    env::*,
+    resolve::{get_alias_installed_target, normalize_alias_target, validate_alias_name},
+    success,
+    versions::Version,
+    warning,
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use console::style;
+
+const LONG_ABOUT: &str = "\
+Manage user defined version aliases.
+
+Aliases can be used anywhere a version is accepted (like `goup use work`). \
+An alias either points to a specific version (`1.21.5`), to the latest \
+release of a minor version (`1.22` or `1.22.x`) or to one of the built-in \
+selectors `stable` and `unstable`.
+
+Use `goup alias <NAME> <TARGET>` to create or update an alias.";
+
+/// Manage version aliases.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT, args_conflicts_with_subcommands = true)]
+pub struct Alias {
+    #[command(subcommand)]
+    command: Option<AliasCommands>,
+
+    /// Name of the alias to create or update.
+    #[arg(requires = "target")]
+    name: Option<String>,
+
+    /// Version or selector the alias points to.
+    target: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum AliasCommands {
+    /// List all aliases.
+    #[command(visible_aliases = ["list"])]
+    Ls,
+
+    /// Remove an alias.
+    #[command(visible_aliases = ["remove", "delete"])]
+    Rm {
+        /// Name of the alias to remove.
+        name: String,
+    },
+}
+
+impl Command for Alias {
+    fn run(&self) -> anyhow::Result<()> {
+        match (&self.command, &self.name, &self.target) {
+            (Some(AliasCommands::Rm { name }), _, _) => remove(name),
+            (_, Some(name), Some(target)) => set(name, target),
+            _ => list(),
+        }
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+fn set(name: &str, target: &str) -> Result<()> {
+    let name = name.to_lowercase();
+    validate_alias_name(&name)?;
+
+    let name_version: Version = name.parse()?;
+    if get_installed_versions()?.contains(&name_version) {
+        anyhow::bail!("An installed SDK is named `{name}`. Please choose another alias name.");
+    }
+
+    let target = normalize_alias_target(target)?;
+
+    State::modify(|state| {
+        state.aliases.insert(name.clone(), target.clone());
+        Ok(())
+    })?;
+
+    success!("Alias {name} now points to {target}.");
+
+    Ok(())
+}
+
+fn remove(name: &str) -> Result<()> {
+    let name = name.to_lowercase();
+
+    State::modify(|state| match state.aliases.remove(&name) {
+        Some(_) => Ok(()),
+        None => anyhow::bail!("No alias found with the name `{name}`."),
+    })?;
+
+    success!("Alias {name} has been removed.");
+
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    let state = State::load()?;
+    if state.aliases.is_empty() {
+        warning!("There are no aliases defined.");
+        return Ok(());
+    }
+
+    let installed = get_installed_versions()?;
+    let width = state.aliases.keys().map(|k| k.len()).max().unwrap_or_default();
+
+    for (name, target) in &state.aliases {
+        let resolved = match get_alias_installed_target(target, &installed) {
+            Some(v) if v.to_string() != *target => {
+                format!(" {}", style(format!("(installed: {v})")).dim())
+            }
+            Some(_) => String::new(),
+            None => format!(" {}", style("(not installed)").yellow()),
+        };
+        println!("{name:<width$}  → {}{resolved}", style(target).cyan());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use super::Command;
+use crate::{
+    cmd::{self, exec, exec_attached},
+    env::*,
+    progress::Spinner,
+    success,
+    tui::print_note,
+    versions::{Version, GOLANG_REPO},
+};
+use anyhow::Result;
+use clap::Args;
+use std::{
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[cfg(not(windows))]
+const MAKE_SCRIPT: &str = "./make.bash";
+#[cfg(windows)]
+const MAKE_SCRIPT: &str = "make.bat";
+
+const LONG_ABOUT: &str = "\
+Build a Go SDK from the Go source repository.
+
+The source is cloned into goup's work directory on the first build and updated \
+on every subsequent build. An already installed SDK is used to bootstrap the build.
+
+The built SDK is registered under a name which can be passed to other commands \
+like `goup use` or `goup drop`. By default, `tip` builds are registered as `tip`, \
+release branches by their branch name and commits as `commit-<hash>`. An existing \
+SDK with the same name is only replaced after the build has succeeded.";
+
+/// Source revision to build an SDK from.
+enum Revision {
+    Tip,
+    Branch(String),
+    Commit(String),
+}
+
+impl Revision {
+    /// Returns the git revision to check out.
+    fn git_rev(&self) -> &str {
+        match self {
+            Self::Tip => "master",
+            Self::Branch(b) => b,
+            Self::Commit(c) => c,
+        }
+    }
+
+    /// Returns the default name the built SDK is
+    /// registered with.
+    fn sdk_name(&self) -> String {
+        match self {
+            Self::Tip => "tip".into(),
+            Self::Branch(b) => b.clone(),
+            Self::Commit(c) => format!("commit-{}", &c[..c.len().min(12)]),
+        }
+    }
+}
+
+impl FromStr for Revision {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => anyhow::bail!("revision must not be empty"),
+            "tip" | "master" => Ok(Self::Tip),
+            s if s.len() >= 7 && s.len() <= 40 && s.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(Self::Commit(s.to_lowercase()))
+            }
+            s => Ok(Self::Branch(s.into())),
+        }
+    }
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tip => write!(f, "tip"),
+            Self::Branch(b) => write!(f, "branch {b}"),
+            Self::Commit(c) => write!(f, "commit {c}"),
+        }
+    }
+}
+
+/// Build an SDK from source.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Build {
+    /// The revision to build; either `tip`, a release branch
+    /// (like `release-branch.go1.24`) or a commit hash.
+    revision: String,
+
+    /// Installed SDK version used to bootstrap the build.
+    /// Defaults to the latest installed release.
+    #[arg(short, long)]
+    bootstrap: Option<String>,
+
+    /// Name the built SDK is registered with. The name
+    /// is case-insensitive and stored in lowercase.
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// Switch to the built SDK after the build succeeded.
+    #[arg(short, long)]
+    select: bool,
+}
+
+impl Command for Build {
+    fn run(&self) -> anyhow::Result<()> {
+        let revision: Revision = self.revision.parse()?;
+
+        // Versions passed to other commands are lowercased, so
+        // the SDK would not be found by a name with capitals.
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| revision.sdk_name())
+            .to_lowercase();
+        let sdk: Version = name.parse()?;
+        if !sdk.is_ident() {
+            anyhow::bail!(
+                "The SDK name must not be a release version. Please choose another name."
+            );
+        }
+
+        let bootstrap = get_bootstrap_version(self.bootstrap.as_deref())?;
+        let bootstrap_root = get_version_installation_dir(&bootstrap)?.join("go");
+
+        let source_dir = get_source_dir()?;
+        {
+            Spinner::new("Updating Go source repository ...");
+            update_source(&source_dir)?;
+        }
+
+        let build_dir = get_builds_dir()?.join(&name);
+        remove_build_dir(&source_dir, &build_dir)?;
+        ensure_dir(get_builds_dir()?)?;
+
+        let goroot = build_dir.join("go");
+        git(
+            &source_dir,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                "--force",
+                &goroot.to_string_lossy(),
+                revision.git_rev(),
+            ],
+        )?;
+
+        print_note(&format!(
+            "Building {revision} using SDK {bootstrap} for bootstrapping ..."
+        ));
+
+        exec_attached(
+            &[MAKE_SCRIPT],
+            Some(&goroot.join("src")),
+            &[("GOROOT_BOOTSTRAP", &bootstrap_root.to_string_lossy())],
+        )
+        .map_err(|err| {
+            anyhow::anyhow!(
+                "Building the SDK failed: {err}\nThe build directory has been kept at {} for inspection.",
+                build_dir.to_string_lossy()
+            )
+        })?;
+
+        // The built SDK must not depend on the source repository,
+        // so the worktree link is removed before moving it into
+        // the installations directory.
+        fs::remove_file(goroot.join(".git"))?;
+        git(&source_dir, &["worktree", "prune"])?;
+
+        let reported = get_built_version(&goroot).unwrap_or_else(|| sdk.to_string());
+        let lock = lock_install(&sdk)?;
+        replace_installation(&sdk, &build_dir)?;
+        register_sdk(
+            &sdk,
+            Sdk {
+                version: reported,
+                source_url: Some(format!("{GOLANG_REPO}#{}", revision.git_rev())),
+                ..Sdk::new(&sdk, Origin::Built)
+            },
+        )?;
+        drop(lock);
+        sync_versioned_binaries()?;
+
+        if self.select {
+            set_current_version(Some(&sdk))?;
+            success!("Built SDK {sdk} from {revision} and switched to it!");
+        } else {
+            success!("Built SDK {sdk} from {revision}!");
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+/// Returns the installed SDK which is used to bootstrap the
+/// build. If `version` is given, the latest installed SDK
+/// covered by it is returned. Otherwise, the latest installed
+/// release is chosen.
+fn get_bootstrap_version(version: Option<&str>) -> Result<Version> {
+    let target: Option<Version> = version.map(|v| v.parse()).transpose()?;
+
+    get_installed_versions()?
+        .into_iter()
+        .filter(|v| match &target {
+            Some(t) => t.covers(v),
+            None => !v.is_ident(),
+        })
+        .max()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No installed SDK found to bootstrap the build.\n\
+                Use `goup use` to install one first."
+            )
+        })
+}
+
+/// Initializes the bare source repository, if not existent,
+/// and fetches all branches and tags from upstream.
+fn update_source(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        ensure_dir(dir)?;
+        git(dir, &["init", "--bare"])?;
+        git(dir, &["remote", "add", "origin", GOLANG_REPO])?;
+    }
+
+    git(
+        dir,
+        &[
+            "fetch",
+            "--prune",
+            "--tags",
+            "origin",
+            "+refs/heads/*:refs/heads/*",
+        ],
+    )
+}
+
+/// Returns the version the SDK built in `goroot` reports
+/// itself, like `devel go1.23-4f1ab2c`.
+fn get_built_version(goroot: &Path) -> Option<String> {
+    ["VERSION", "VERSION.cache"]
+        .iter()
+        .find_map(|f| fs::read_to_string(goroot.join(f)).ok())
+        .and_then(|v| v.lines().next().map(|l| l.trim().to_string()))
+        .filter(|v| !v.is_empty())
+}
+
+/// Removes a previous build directory, if existent.
+fn remove_build_dir(source_dir: &Path, build_dir: &Path) -> Result<()> {
+    if build_dir.exists() {
+        fs::remove_dir_all(build_dir)?;
+        git(source_dir, &["worktree", "prune"])?;
+    }
+    Ok(())
+}
+
+/// Moves the SDK built in `build_dir` into the installations
+/// directory. A previously installed SDK with the same name is
+/// only removed after the new SDK has been moved next to it.
+fn replace_installation(sdk: &Version, build_dir: &Path) -> Result<()> {
+    let install_dir = get_version_installation_dir(sdk)?;
+    ensure_dir(get_installations_dir()?)?;
+
+    let mut old_dir = build_dir.as_os_str().to_owned();
+    old_dir.push(".old");
+    let old_dir = PathBuf::from(old_dir);
+
+    if install_dir.exists() {
+        if old_dir.exists() {
+            fs::remove_dir_all(&old_dir)?;
+        }
+        fs::rename(&install_dir, &old_dir)?;
+    }
+
+    fs::rename(build_dir, &install_dir)?;
+
+    if old_dir.exists() {
+        fs::remove_dir_all(&old_dir)?;
+    }
+
+    Ok(())
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<()> {
+    let mut cmd = vec!["git", "--git-dir"];
+    let repo = repo.to_string_lossy();
+    cmd.push(&repo);
+    cmd.extend(args);
+
+    match exec(&cmd) {
+        Ok(_) => Ok(()),
+        Err(cmd::errors::Error::NotFound) => anyhow::bail!(
+            "Seems you don't have git installed on your system. Building from source requires git."
+        ),
+        Err(err) => Err(err.into()),
+    }
+}
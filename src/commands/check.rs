@@ -1,74 +1,408 @@
 use std::env;
 
-use super::Command;
+use super::{Command, Exit};
 use crate::{
     env::*,
+    error,
+    policy::{get_policy, Policy},
     progress::Spinner,
     shell, success,
+    tui::{eprint_warning, print_warning},
     versions::{self, get_upstream_versions, Version},
     warning,
 };
-use clap::Args;
+use clap::{Args, ValueEnum};
 use console::style;
+use serde::Serialize;
+
+/// Exit code when a new pre-release is available.
+const EXIT_PRE: i32 = 10;
+/// Exit code when a new patch release is available.
+const EXIT_PATCH: i32 = 11;
+/// Exit code when a new minor release is available.
+const EXIT_MINOR: i32 = 12;
+/// Exit code when a new release with security fixes is available.
+const EXIT_SECURITY: i32 = 13;
+/// Exit code when an SDK is not supported anymore
+/// and `--fail-on-eol` is passed.
+const EXIT_EOL: i32 = 20;
+/// Exit code when an SDK violates the version policy.
+const EXIT_POLICY: i32 = 21;
+
+fn get_long_about() -> String {
+    format!(
+        "Check for updates.\n\n\
+        The exit code indicates which updates are available. If updates of multiple \
+        kinds are available, the code of the most significant one is used.\n\n  \
+        0    up to date\n  \
+        {EXIT_PRE}   new pre-release available\n  \
+        {EXIT_PATCH}   new patch release available\n  \
+        {EXIT_MINOR}   new minor release available\n  \
+        {EXIT_SECURITY}   new release with security fixes available\n  \
+        {EXIT_EOL}   SDK is not supported anymore (only with `--fail-on-eol`)\n  \
+        {EXIT_POLICY}   SDK violates the version policy\n  \
+        1    an error occurred"
+    )
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
 
 /// Check for updates.
 #[derive(Args)]
+#[command(long_about = get_long_about())]
 pub struct Check {
     #[arg(short, long)]
     /// Only print when updates are available;
     /// Designed to be used in profile file.
     notify: bool,
+
+    /// Check all installed SDKs instead of only
+    /// the currently selected one.
+    #[arg(short, long)]
+    all: bool,
+
+    /// The output format.
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Exit with code 20 if a checked SDK is not
+    /// supported anymore.
+    #[arg(long)]
+    fail_on_eol: bool,
+
+    /// Only print and report updates which include
+    /// security fixes; Implies `--notify`.
+    #[arg(short, long)]
+    security_only: bool,
+}
+
+/// Available updates of an installed SDK.
+#[derive(Serialize)]
+struct Report {
+    version: String,
+    current: bool,
+    pre: Option<String>,
+    patch: Option<String>,
+    minor: Option<String>,
+    unsupported_since: Option<String>,
+    security_releases: Vec<String>,
+    policy_violation: Option<String>,
+}
+
+impl Report {
+    fn new(
+        policy: &Policy,
+        upstream_versions: &[Version],
+        security_releases: &[Version],
+        version: &Version,
+        current: bool,
+    ) -> Self {
+        Self {
+            version: version.to_string(),
+            current,
+            pre: versions::get_new_pre(upstream_versions, version).map(ToString::to_string),
+            patch: versions::get_new_patch(upstream_versions, version).map(ToString::to_string),
+            minor: versions::get_new_minor(upstream_versions, version).map(ToString::to_string),
+            unsupported_since: versions::get_unsupported_since(upstream_versions, version)
+                .map(ToString::to_string),
+            security_releases: versions::get_missing_security_releases(security_releases, version)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            policy_violation: policy
+                .check(version, upstream_versions)
+                .map(|v| v.to_string()),
+        }
+    }
+
+    fn is_up_to_date(&self) -> bool {
+        self.pre.is_none() && self.patch.is_none() && self.minor.is_none()
+    }
+
+    fn exit_code(&self) -> i32 {
+        if !self.security_releases.is_empty() {
+            EXIT_SECURITY
+        } else if self.minor.is_some() {
+            EXIT_MINOR
+        } else if self.patch.is_some() {
+            EXIT_PATCH
+        } else if self.pre.is_some() {
+            EXIT_PRE
+        } else {
+            0
+        }
+    }
 }
 
 impl Command for Check {
     fn run(&self) -> anyhow::Result<()> {
-        check_env_applied(&shell::get_shell())?;
+        // Warnings would break the output in JSON format.
+        if let Format::Text = self.format {
+            check_env_applied(&shell::get_shell())?;
+        }
 
-        let Some(current) = get_current_version()? else {
-            warning!(
-                "No version has been selected.\n\
-                      Use `goup use` to select an SDK version."
-            );
-            return Ok(());
+        let current = get_current_version()?;
+
+        let versions: Vec<_> = if self.all {
+            let mut installed = get_installed_versions()?;
+            installed.sort();
+            installed.into_iter().filter(|v| !v.is_ident()).collect()
+        } else {
+            match &current {
+                None => {
+                    self.warn(
+                        "No version has been selected.\n\
+                        Use `goup use` to select an SDK version.",
+                    );
+                    return self.print_empty();
+                }
+                Some(c) if c.is_ident() => {
+                    if !self.is_notify() {
+                        self.warn(&format!("The selected SDK {c} is no upstream release and can not be checked for updates."));
+                    }
+                    return self.print_empty();
+                }
+                Some(c) => vec![c.clone()],
+            }
+        };
+
+        if versions.is_empty() {
+            self.warn("There are no SDKs installed which could be checked for updates.");
+            return self.print_empty();
+        }
+
+        let (upstream_versions, security_releases) = match self.format {
+            Format::Text => {
+                Spinner::new("Checking ...");
+                (get_upstream_versions()?, self.get_security_releases()?)
+            }
+            Format::Json => (get_upstream_versions()?, self.get_security_releases()?),
         };
 
-        Spinner::new("Checking ...");
+        let policy = get_policy()?;
+        let mut reports: Vec<_> = versions
+            .iter()
+            .map(|v| {
+                Report::new(
+                    policy,
+                    &upstream_versions,
+                    &security_releases,
+                    v,
+                    current.as_ref() == Some(v),
+                )
+            })
+            .collect();
 
-        let upstream_versions = get_upstream_versions()?;
+        if self.security_only {
+            reports.retain(|r| !r.security_releases.is_empty());
+            if reports.is_empty() {
+                return self.print_empty();
+            }
+        }
 
-        let new_minor = versions::get_new_minor(&upstream_versions, &current);
-        let new_patch = versions::get_new_patch(&upstream_versions, &current);
-        let new_pre = versions::get_new_pre(&upstream_versions, &current);
+        match self.format {
+            Format::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+            Format::Text if self.all => self.print_all(&reports),
+            Format::Text => self.print_current(&reports[0]),
+        }
+
+        if let Format::Text = self.format {
+            if reports.iter().any(|r| r.unsupported_since.is_some()) {
+                let supported: Vec<_> = versions::get_supported_minors(&upstream_versions)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                warning!(
+                    "Supported versions receiving security fixes are {}.",
+                    supported.join(" and ")
+                );
+            }
+        }
+
+        if reports.iter().any(|r| r.policy_violation.is_some()) {
+            return Err(Exit(EXIT_POLICY).into());
+        }
+
+        if self.fail_on_eol && reports.iter().any(|r| r.unsupported_since.is_some()) {
+            return Err(Exit(EXIT_EOL).into());
+        }
+
+        match reports.iter().map(Report::exit_code).max() {
+            Some(code) if code != 0 => Err(Exit(code).into()),
+            _ => Ok(()),
+        }
+    }
 
-        if new_pre.is_none() && new_minor.is_none() && new_patch.is_none() {
-            if !self.notify {
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+impl Check {
+    fn is_notify(&self) -> bool {
+        self.notify || self.security_only
+    }
+
+    /// Prints the given warning. In JSON format, it is printed
+    /// to stderr to keep the output parsable.
+    fn warn(&self, msg: &str) {
+        match self.format {
+            Format::Text => print_warning(msg),
+            Format::Json => eprint_warning(msg),
+        }
+    }
+
+    /// Prints the result when there are no reports, which is
+    /// an empty list in JSON format.
+    fn print_empty(&self) -> anyhow::Result<()> {
+        if let Format::Json = self.format {
+            println!("[]");
+        }
+        Ok(())
+    }
+
+    /// Returns the releases including security fixes. Failing to
+    /// fetch the release history is only fatal when checking for
+    /// security updates only.
+    fn get_security_releases(&self) -> anyhow::Result<Vec<Version>> {
+        match versions::get_security_releases() {
+            Ok(v) => Ok(v),
+            Err(err) if self.security_only => Err(err),
+            Err(err) => {
+                self.warn(&format!("Fetching release history failed, security fixes can not be checked.\nError was: {err}"));
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn print_current(&self, report: &Report) {
+        if let Some(violation) = &report.policy_violation {
+            error!("Policy violation: {violation}.");
+        }
+
+        if let Some(since) = &report.unsupported_since {
+            warning!(
+                "SDK {} is unsupported since {since} and does not receive security fixes anymore.",
+                report.version
+            );
+        }
+
+        if report.is_up_to_date() {
+            if !self.is_notify() {
                 success!("You are up to date with the latest upstream version!");
             }
-        } else {
+            return;
+        }
+
+        if !report.security_releases.is_empty() {
+            warning!("New Go versions including security fixes are available!");
+            println!(
+                "{}:  {} → {} {}",
+                style("security").red().bold(),
+                style(&report.version).dim(),
+                style(report.patch.as_deref().unwrap_or_default()).cyan(),
+                style(format!(
+                    "(security fixes in {})",
+                    report.security_releases.join(", ")
+                ))
+                .red()
+            );
+        }
+
+        if !self.security_only {
             success!("New Go versions are available!");
-            checkprint("pre-release", &current, new_pre);
-            checkprint("minor", &current, new_minor);
-            checkprint("patch", &current, new_patch);
-
-            if self.notify {
-                let binname = env::current_exe()
-                    .ok()
-                    .and_then(|pb| pb.file_name().map(|s| s.to_string_lossy().to_string()))
-                    .unwrap_or("goup".into());
-                success!("\nUse `{binname} use` to upgrade.");
+            checkprint("pre-release", &report.version, report.pre.as_deref());
+            checkprint("minor", &report.version, report.minor.as_deref());
+            checkprint("patch", &report.version, report.patch.as_deref());
+        }
+
+        if self.is_notify() {
+            let binname = env::current_exe()
+                .ok()
+                .and_then(|pb| pb.file_name().map(|s| s.to_string_lossy().to_string()))
+                .unwrap_or("goup".into());
+            success!("\nUse `{binname} use` to upgrade.");
+        }
+    }
+
+    fn print_all(&self, reports: &[Report]) {
+        if reports
+            .iter()
+            .all(|r| r.is_up_to_date() && r.unsupported_since.is_none() && r.policy_violation.is_none())
+        {
+            if !self.is_notify() {
+                success!("All installed SDKs are up to date with the latest upstream versions!");
             }
+            return;
         }
 
-        Ok(())
+        let width = reports
+            .iter()
+            .map(|r| r.version.len())
+            .max()
+            .unwrap_or_default()
+            .max("version".len());
+
+        println!(
+            "  {}",
+            style(format!(
+                "{:<width$}  {:<10} {:<10} {:<10}",
+                "version", "patch", "minor", "pre-release"
+            ))
+            .dim()
+        );
+
+        for r in reports {
+            if self.is_notify()
+                && r.is_up_to_date()
+                && r.unsupported_since.is_none()
+                && r.policy_violation.is_none()
+            {
+                continue;
+            }
+
+            let cell = |v: &Option<String>| match v {
+                Some(v) => style(format!("{v:<10}")).cyan(),
+                None => style(format!("{:<10}", "-")).dim(),
+            };
+            let marker = if r.current { "*" } else { " " };
+            let patch = if r.security_releases.is_empty() {
+                cell(&r.patch)
+            } else {
+                cell(&r.patch).red().bold()
+            };
+            let mut notes = String::new();
+            if !r.security_releases.is_empty() {
+                notes += &format!(" {}", style("(security fixes)").red());
+            }
+            if let Some(violation) = &r.policy_violation {
+                notes += &format!(" {}", style(format!("(policy: {violation})")).red());
+            }
+            if let Some(since) = &r.unsupported_since {
+                notes += &format!(" {}", style(format!("(unsupported since {since})")).yellow());
+            }
+
+            println!(
+                "{marker} {:<width$}  {patch} {} {}{notes}",
+                r.version,
+                cell(&r.minor),
+                cell(&r.pre)
+            );
+        }
     }
 }
 
-fn checkprint(typ: &str, current: &Version, v: Option<&Version>) {
+fn checkprint(typ: &str, current: &str, v: Option<&str>) {
     if let Some(new) = v {
         println!(
             "{}:  {} → {}",
             style(typ).magenta(),
-            style(current.to_string()).dim(),
+            style(current).dim(),
             style(new).cyan()
         );
     }
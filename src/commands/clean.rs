@@ -1,13 +1,17 @@
-use crate::{env::*, progress::Spinner, tui::*};
+use crate::{env::*, progress::Spinner, tui::*, versions::Version};
 
 use super::Command;
 use clap::Args;
 
-/// Remove all installed SDKs.
+/// Remove all installed SDKs except pinned ones.
+///
+/// SDKs provided by the system store are never removed. Use
+/// `goup --system clean` to clean up the system store itself.
 #[derive(Args)]
-#[command(visible_aliases = ["purge", "prune"])]
+#[command(visible_aliases = ["purge"])]
 pub struct Clean {
-    /// Clean up **all** installed SDK versions
+    /// Clean up **all** installed SDK versions, including the
+    /// currently selected one. Pinned SDKs are kept.
     #[arg(short, long)]
     all: bool,
 }
@@ -16,16 +20,20 @@ impl Command for Clean {
     fn run(&self) -> anyhow::Result<()> {
         Spinner::new("Removing SDKs ...");
 
-        if self.all {
-            link_current_version(None)?;
-            write_current_version(None)?;
+        let pinned = get_pinned_versions()?;
+        let curr = get_current_version()?;
+
+        if self.all && pinned.is_empty() {
+            set_current_version(None)?;
             drop_install_dir()?;
         } else {
+            if self.all && curr.as_ref().is_some_and(|c| !pinned.contains(c)) {
+                set_current_version(None)?;
+            }
+
             let versions = get_installed_versions()?;
-            let curr = get_current_version()?;
-            let errs: Vec<_> = versions
-                .iter()
-                .filter(|v| Some(*v) != curr.as_ref())
+            let errs: Vec<_> = get_removable(&versions, curr.as_ref(), &pinned, self.all)
+                .into_iter()
                 .map(|v| (v, drop_version(v)))
                 .filter(|(_, r)| r.is_err())
                 .map(|(v, r)| format!("- {}: {}", v, r.unwrap_err()))
@@ -39,8 +47,61 @@ impl Command for Clean {
             }
         }
 
+        sync_versioned_binaries()?;
+
         print_success("SDKs have been cleaned up.");
 
+        if !pinned.is_empty() {
+            let pinned: Vec<_> = pinned.iter().map(|v| v.to_string()).collect();
+            print_note(&format!("Pinned SDKs have been kept: {}", pinned.join(", ")));
+        }
+
         Ok(())
     }
 }
+
+/// Returns the versions of `installed` which are removed when
+/// cleaning up. Pinned SDKs are always kept and the `current`
+/// SDK is only removed if `all` is set.
+fn get_removable<'a>(
+    installed: &'a [Version],
+    current: Option<&Version>,
+    pinned: &[Version],
+    all: bool,
+) -> Vec<&'a Version> {
+    installed
+        .iter()
+        .filter(|v| all || Some(*v) != current)
+        .filter(|v| !pinned.contains(v))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn versions(v: &[&str]) -> Vec<Version> {
+        v.iter().map(|v| v.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_get_removable() {
+        let installed = versions(&["1.21.0", "1.22.0", "1.22.1", "tip"]);
+        let current = "1.22.1".parse().unwrap();
+        let pinned = versions(&["1.21.0", "1.22.1"]);
+        let removable = |current: Option<&Version>, all: bool| -> Vec<String> {
+            get_removable(&installed, current, &pinned, all)
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+
+        assert_eq!(vec!["1.22.0", "tip"], removable(Some(&current), false));
+        assert_eq!(vec!["1.22.0", "tip"], removable(Some(&current), true));
+        assert_eq!(vec!["1.22.0", "tip"], removable(None, false));
+
+        let current = "tip".parse().unwrap();
+        assert_eq!(vec!["1.22.0"], removable(Some(&current), false));
+        assert_eq!(vec!["1.22.0", "tip"], removable(Some(&current), true));
+    }
+}
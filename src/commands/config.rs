@@ -0,0 +1,147 @@
+use super::Command;
+use crate::{
+    config::{self, get_config_file, get_key, Source, KEYS},
+    env::LockMode,
+    success, warning,
+};
+use clap::{Args, Subcommand};
+use console::style;
+use std::env;
+
+/// Reads the configuration file, explaining how to repair it
+/// if it cannot be parsed.
+fn load_config() -> anyhow::Result<config::Config> {
+    config::Config::load().map_err(|err| {
+        anyhow::anyhow!(
+            "{err}\nEdit the file by hand to fix the syntax error."
+        )
+    })
+}
+
+fn get_long_about() -> String {
+    let keys: Vec<_> = KEYS
+        .iter()
+        .map(|k| format!("  {:<20} {} [env: {}]", k.name, k.description, k.env))
+        .collect();
+
+    format!(
+        "Manage goup's configuration.\n\n\
+        The configuration is stored in `$XDG_CONFIG_HOME/goup/config.toml` by default, \
+        which can be changed via the environment variable `{}`. Each value can be \
+        overridden via the listed environment variable.\n\n\
+        Available keys:\n{}",
+        config::CONFIG_ENV,
+        keys.join("\n")
+    )
+}
+
+/// Manage the configuration.
+#[derive(Args)]
+#[command(long_about = get_long_about())]
+pub struct Config {
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective value of a key.
+    Get {
+        /// Name of the key.
+        key: String,
+    },
+
+    /// Set the value of a key in the configuration file.
+    Set {
+        /// Name of the key.
+        key: String,
+
+        /// Value to set.
+        value: String,
+    },
+
+    /// Remove a key from the configuration file.
+    #[command(visible_aliases = ["rm"])]
+    Unset {
+        /// Name of the key.
+        key: String,
+    },
+
+    /// List the effective values of all keys.
+    #[command(visible_aliases = ["ls"])]
+    List,
+
+    /// Print the path of the configuration file.
+    Path,
+}
+
+impl Command for Config {
+    fn run(&self) -> anyhow::Result<()> {
+        match &self.command {
+            ConfigCommands::Get { key } => {
+                let (value, _) = load_config()?.get(key)?;
+                if let Some(value) = value {
+                    println!("{value}");
+                }
+            }
+            ConfigCommands::Set { key, value } => {
+                let mut config = load_config()?;
+                config.set(key, value)?;
+                config.save()?;
+                success!("{key} has been set to {value}.");
+                warn_overridden(key);
+            }
+            ConfigCommands::Unset { key } => {
+                let mut config = load_config()?;
+                config.unset(key)?;
+                config.save()?;
+                success!("{key} has been unset.");
+                warn_overridden(key);
+            }
+            ConfigCommands::List => {
+                let config = load_config()?;
+                for key in KEYS {
+                    let (value, source, _) = config.lookup(key);
+                    let source = match source {
+                        Source::Env(env) => format!("(from {env})"),
+                        Source::File => String::new(),
+                        Source::Default => "(default)".into(),
+                        Source::Unset => "(unset)".into(),
+                    };
+                    println!(
+                        "{:<20} {} {}",
+                        key.name,
+                        style(value.unwrap_or_default()).cyan(),
+                        style(source).dim()
+                    );
+                }
+
+                let problems = config.problems();
+                if !problems.is_empty() {
+                    println!();
+                }
+                for problem in problems {
+                    warning!("Ignoring {problem}.");
+                }
+            }
+            ConfigCommands::Path => println!("{}", get_config_file()?.display()),
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::None
+    }
+}
+
+fn warn_overridden(key: &str) {
+    if let Ok(key) = get_key(key) {
+        if env::var(key.env).is_ok_and(|v| !v.is_empty()) {
+            warning!(
+                "The value is overridden by the environment variable {}.",
+                key.env
+            );
+        }
+    }
+}
@@ -2,9 +2,12 @@ use super::Command;
 use crate::{
     cmd::{self, exec},
     env::*,
-    error, shell, warning,
+    error, shell,
+    versions::{get_cached_upstream_versions, get_unsupported_since},
+    warning,
 };
 use clap::Args;
+use console::style;
 
 /// Display the currently selected version of Go.
 #[derive(Args)]
@@ -16,7 +19,16 @@ impl Command for Current {
         check_env_applied(&shell::get_shell())?;
 
         if let Some(v) = get_current_version()? {
-            println!("{v}");
+            match get_current_channel()? {
+                Some(channel) => println!("{v} {}", style(format!("(channel {channel})")).dim()),
+                None => println!("{v}"),
+            }
+
+            let upstream_versions = get_cached_upstream_versions().unwrap_or_default();
+            if let Some(since) = get_unsupported_since(&upstream_versions, &v) {
+                warning!("SDK {v} is unsupported since {since} and does not receive security fixes anymore.");
+            }
+
             return Ok(());
         }
 
@@ -31,4 +43,8 @@ impl Command for Current {
 
         Ok(())
     }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
 }
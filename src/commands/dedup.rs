@@ -0,0 +1,39 @@
+use super::Command;
+use crate::{env::*, progress::Spinner, success};
+use clap::Args;
+use indicatif::HumanBytes;
+
+const LONG_ABOUT: &str = "\
+Replace identical files of installed SDKs by hard links.
+
+Consecutive releases share most of their files, like sources, \
+test data and documentation. Files which are identical in size, \
+permissions and contents are replaced by hard links to a single \
+copy. Dropping an SDK only removes its own links, so the files of \
+other SDKs stay intact.
+
+To deduplicate automatically after installing an SDK, set the \
+`dedup` configuration key to `true`. Deduplication is not \
+supported on Windows.";
+
+/// Hard-link identical files of installed SDKs.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Dedup {}
+
+impl Command for Dedup {
+    fn run(&self) -> anyhow::Result<()> {
+        let res = {
+            let _spinner = Spinner::new("Deduplicating SDKs ...");
+            dedup_installations()?
+        };
+
+        success!(
+            "Replaced {} files by hard links, saving {}.",
+            res.linked,
+            HumanBytes(res.saved)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,509 @@
+use super::{env::apply_profile, env::PROFILE_MARKER, Command, Exit};
+use crate::{
+    cmd::exec,
+    env::*,
+    shell::{self, ShellEnv},
+    success,
+};
+use anyhow::Result;
+use clap::Args;
+use console::style;
+use std::{env, fs, path::Path};
+
+const LONG_ABOUT: &str = "\
+Diagnose the environment and the health of installed SDKs.
+
+The following is checked:
+  - the selected SDK matches the `current` link and the link is not dangling
+  - installed SDKs contain a go binary and are recorded in the state file
+  - the versioned launchers (like `go1.22.3`) match the available SDKs
+  - `GOROOT` points to the selected SDK
+  - no other go binary shadows goup's SDK in `PATH`
+  - `GOTOOLCHAIN` does not override the selected SDK
+  - the profile file applies goup's environment variables
+
+Findings which can be repaired automatically are fixed when passing `--fix`. \
+The command exits with code 1 if errors remain.";
+
+/// Diagnose the environment and installed SDKs.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Doctor {
+    /// Repair findings which can be fixed automatically.
+    #[arg(long)]
+    fix: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+type Fix = Box<dyn FnOnce() -> Result<String>>;
+
+/// The result of a single check.
+struct Finding {
+    check: &'static str,
+    severity: Severity,
+    message: String,
+    hints: Vec<String>,
+    fix: Option<Fix>,
+}
+
+impl Finding {
+    fn new(check: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity,
+            message: message.into(),
+            hints: vec![],
+            fix: None,
+        }
+    }
+
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Ok, message)
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
+    fn fix(mut self, fix: impl FnOnce() -> Result<String> + 'static) -> Self {
+        self.fix = Some(Box::new(fix));
+        self
+    }
+}
+
+impl Command for Doctor {
+    fn run(&self) -> anyhow::Result<()> {
+        // The working directory is locked here instead of by the caller,
+        // because an inconsistent selection would otherwise be repaired
+        // before it could be diagnosed.
+        let _lock = lock_work_dir(if self.fix {
+            LockMode::Exclusive
+        } else {
+            LockMode::Shared
+        })?;
+
+        let checks: [fn() -> Result<Finding>; 7] = [
+            check_selection,
+            check_installations,
+            check_launchers,
+            check_goroot,
+            check_path,
+            check_gotoolchain,
+            check_profile,
+        ];
+
+        let mut errors = 0;
+        let mut warnings = 0;
+        for check in checks {
+            match self.print_finding(check()?) {
+                Some(Severity::Error) => errors += 1,
+                Some(Severity::Warning) => warnings += 1,
+                Some(Severity::Ok) | None => {}
+            }
+        }
+
+        println!();
+
+        if errors > 0 {
+            return Err(Exit(1).into());
+        }
+
+        if warnings == 0 {
+            success!("No problems found.");
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::None
+    }
+}
+
+impl Doctor {
+    /// Prints the given finding and applies its fix if requested.
+    /// Returns the severity of the finding after fixing, or
+    /// [`None`] if it has been fixed.
+    fn print_finding(&self, finding: Finding) -> Option<Severity> {
+        let symbol = match finding.severity {
+            Severity::Ok => style("✔").green(),
+            Severity::Warning => style("!").yellow(),
+            Severity::Error => style("✘").red(),
+        };
+        println!("{symbol} {:<20} {}", finding.check, finding.message);
+        for hint in &finding.hints {
+            println!("  {:<20} {}", "", style(hint).dim());
+        }
+
+        if finding.severity == Severity::Ok {
+            return Some(Severity::Ok);
+        }
+
+        match finding.fix {
+            Some(fix) if self.fix => match fix() {
+                Ok(msg) => {
+                    println!("  {:<20} {}", "", style(format!("fixed: {msg}")).green());
+                    None
+                }
+                Err(err) => {
+                    println!("  {:<20} {}", "", style(format!("fix failed: {err}")).red());
+                    Some(finding.severity)
+                }
+            },
+            Some(_) => {
+                println!(
+                    "  {:<20} {}",
+                    "",
+                    style("can be fixed using `goup doctor --fix`").cyan()
+                );
+                Some(finding.severity)
+            }
+            None => Some(finding.severity),
+        }
+    }
+}
+
+const SELECTION: &str = "Selected SDK";
+
+fn check_selection() -> Result<Finding> {
+    let recorded = get_current_version()?;
+    let linked = get_linked_version()?;
+    let link_exists = fs::symlink_metadata(get_current_link_dir()?).is_ok();
+
+    let finding = match (&recorded, &linked) {
+        (Some(r), Some(l)) if r == l => return Ok(Finding::ok(SELECTION, r.to_string())),
+        (None, None) if !link_exists => {
+            return Ok(Finding::new(SELECTION, Severity::Warning, "no SDK selected")
+                .hint("Use `goup use` to install and select an SDK."))
+        }
+        (Some(r), Some(l)) => Finding::new(
+            SELECTION,
+            Severity::Error,
+            format!("{r} is selected, but `current` links to {l}"),
+        ),
+        (Some(r), None) if link_exists => Finding::new(
+            SELECTION,
+            Severity::Error,
+            format!("{r} is selected, but the `current` link is dangling"),
+        ),
+        (Some(r), None) => Finding::new(
+            SELECTION,
+            Severity::Error,
+            format!("{r} is selected, but the `current` link is missing"),
+        ),
+        (None, Some(l)) => Finding::new(
+            SELECTION,
+            Severity::Error,
+            format!("no SDK is selected, but `current` links to {l}"),
+        ),
+        (None, None) => Finding::new(
+            SELECTION,
+            Severity::Error,
+            "no SDK is selected, but a dangling `current` link exists",
+        ),
+    };
+
+    Ok(finding
+        .hint("The SDK might have been removed manually or goup has been interrupted.")
+        .fix(|| {
+            Ok(repair_current_selection()?
+                .unwrap_or_else(|| "the selection is consistent".into()))
+        }))
+}
+
+const INSTALLATIONS: &str = "Installed SDKs";
+
+fn check_installations() -> Result<Finding> {
+    let installed = get_installed_versions()?;
+    let state = State::load()?;
+
+    let broken: Vec<_> = installed
+        .iter()
+        .filter(|v| {
+            get_version_installation_dir(v)
+                .map(|dir| !dir.join("go").join("bin").join(GO_BINARY).is_file())
+                .unwrap_or(true)
+        })
+        .map(|v| v.to_string())
+        .collect();
+
+    if !broken.is_empty() {
+        return Ok(Finding::new(
+            INSTALLATIONS,
+            Severity::Error,
+            format!("no go binary found in {}", broken.join(", ")),
+        )
+        .hint("Remove the SDKs using `goup drop` and install them again."));
+    }
+
+    let untracked: Vec<_> = installed
+        .iter()
+        .filter(|v| state.sdk(v).is_none())
+        .cloned()
+        .collect();
+    let stale: Vec<_> = state
+        .sdks
+        .keys()
+        .filter(|v| !installed.iter().any(|i| &i.to_string() == *v))
+        .cloned()
+        .collect();
+
+    if untracked.is_empty() && stale.is_empty() {
+        return Ok(Finding::ok(
+            INSTALLATIONS,
+            format!("{} SDK(s) installed", installed.len()),
+        ));
+    }
+
+    let mut finding = Finding::new(
+        INSTALLATIONS,
+        Severity::Warning,
+        "state file does not match installed SDKs",
+    );
+    if !untracked.is_empty() {
+        let v: Vec<_> = untracked.iter().map(|v| v.to_string()).collect();
+        finding = finding.hint(format!("Not recorded: {}", v.join(", ")));
+    }
+    if !stale.is_empty() {
+        finding = finding.hint(format!("Not installed anymore: {}", stale.join(", ")));
+    }
+
+    Ok(finding.fix(move || {
+        State::modify(|state| {
+            for v in &untracked {
+                state.sdks.insert(v.to_string(), Sdk::from_installation(v)?);
+            }
+            for v in &stale {
+                state.sdks.remove(v);
+            }
+            Ok("the state file has been updated".into())
+        })
+    }))
+}
+
+const LAUNCHERS: &str = "Versioned launchers";
+
+fn check_launchers() -> Result<Finding> {
+    let dir = get_versioned_bin_dir()?;
+    let expected: Vec<_> = get_available_versions()?
+        .iter()
+        .filter(|v| !v.is_ident())
+        .map(|v| format!("go{v}"))
+        .collect();
+
+    let existing: Vec<_> = match dir.read_dir() {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_suffix(".cmd").map(Into::into).unwrap_or(name)
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    let missing = expected.iter().filter(|n| !existing.contains(n)).count();
+    let obsolete = existing.iter().filter(|n| !expected.contains(n)).count();
+
+    if missing == 0 && obsolete == 0 {
+        return Ok(Finding::ok(LAUNCHERS, format!("{} launcher(s)", expected.len())));
+    }
+
+    Ok(Finding::new(
+        LAUNCHERS,
+        Severity::Warning,
+        format!("{missing} missing and {obsolete} obsolete launcher(s)"),
+    )
+    .hint("Launchers allow the go command to switch to SDKs installed via goup.")
+    .fix(|| {
+        sync_versioned_binaries()?;
+        Ok("the launchers have been recreated".into())
+    }))
+}
+
+const GOROOT: &str = "GOROOT";
+
+fn check_goroot() -> Result<Finding> {
+    let shell = shell::get_shell();
+    let expected = shell.path_to_string(get_current_install_dir()?)?;
+
+    let finding = match env::var("GOROOT") {
+        Ok(v) if shell.is_env_applied()? => Finding::ok(GOROOT, v),
+        Ok(v) if !v.is_empty() => Finding::new(
+            GOROOT,
+            Severity::Error,
+            format!("points to {v} instead of {expected}"),
+        )
+        .hint("It might be set by an IDE or another tool. The go command then uses that SDK."),
+        _ => Finding::new(GOROOT, Severity::Error, "is not set"),
+    };
+
+    if finding.severity == Severity::Ok {
+        return Ok(finding);
+    }
+
+    Ok(finding.hint(format!(
+        "Apply goup's environment variables using `{}`.",
+        shell.get_apply_env_command()?
+    )))
+}
+
+const PATH: &str = "PATH";
+
+fn check_path() -> Result<Finding> {
+    let bin_dir = get_current_bin_dir()?;
+    let path = env::var_os("PATH").unwrap_or_default();
+
+    let first = env::split_paths(&path).find(|dir| dir.join(GO_BINARY).is_file());
+    let finding = match first {
+        Some(dir) if is_same_dir(&dir, &bin_dir) => {
+            return Ok(Finding::ok(PATH, format!("go resolves to {}", dir.display())))
+        }
+        Some(dir) => Finding::new(
+            PATH,
+            Severity::Error,
+            format!("go resolves to {}", dir.join(GO_BINARY).display()),
+        )
+        .hint("This go binary shadows the selected SDK, because its directory is listed earlier in PATH."),
+        None => Finding::new(PATH, Severity::Error, "no go binary found"),
+    };
+
+    let shell = shell::get_shell();
+    Ok(finding.hint(format!(
+        "Make sure `{}` is evaluated after other tools modify PATH in your profile.",
+        shell.get_apply_env_command()?
+    )))
+}
+
+const GOTOOLCHAIN: &str = "GOTOOLCHAIN";
+
+/// How a `GOTOOLCHAIN` setting affects the selected SDK.
+#[derive(Debug, PartialEq, Eq)]
+enum Toolchain {
+    /// Only the selected SDK is used.
+    Local,
+    /// SDKs required by modules are looked up in PATH.
+    Path,
+    /// SDKs required by modules are downloaded.
+    Auto,
+    /// The given toolchain is used instead of the selected SDK.
+    Forced(String),
+}
+
+/// Classifies the given `GOTOOLCHAIN` value, where `local+auto`
+/// and `local+path` are the canonical forms of `auto` and `path`.
+fn classify_toolchain(value: &str) -> Toolchain {
+    let (name, suffix) = match value.split_once('+') {
+        Some((name, suffix)) => (name, Some(suffix)),
+        None => (value, None),
+    };
+
+    match (name, suffix) {
+        ("local", None) => Toolchain::Local,
+        ("path", None) | ("local", Some("path")) => Toolchain::Path,
+        ("" | "auto", None) | ("local", Some("auto")) => Toolchain::Auto,
+        (name, _) => Toolchain::Forced(name.into()),
+    }
+}
+
+fn check_gotoolchain() -> Result<Finding> {
+    let from_env = env::var(GOTOOLCHAIN).ok();
+
+    let value = match &from_env {
+        Some(v) => v.clone(),
+        None => {
+            let go = get_current_bin_dir()?.join(GO_BINARY);
+            if !go.is_file() {
+                return Ok(Finding::ok(GOTOOLCHAIN, "not set"));
+            }
+            exec(&[&go.to_string_lossy(), "env", GOTOOLCHAIN])
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        }
+    };
+
+    let finding = match classify_toolchain(&value) {
+        Toolchain::Local | Toolchain::Path => return Ok(Finding::ok(GOTOOLCHAIN, value)),
+        Toolchain::Auto => {
+            return Ok(Finding::new(GOTOOLCHAIN, Severity::Warning, value)
+                .hint("The go command downloads toolchains required by modules instead of using goup's SDKs.")
+                .hint("Set GOTOOLCHAIN to `path` to use SDKs installed via goup instead."))
+        }
+        Toolchain::Forced(name) => Finding::new(
+            GOTOOLCHAIN,
+            Severity::Error,
+            format!("{value} overrides the selected SDK"),
+        )
+        .hint(format!("The go command uses the toolchain {name} instead of the selected SDK.")),
+    };
+
+    match from_env {
+        Some(_) => Ok(finding.hint("It is set as environment variable.")),
+        None => Ok(finding
+            .hint("It is set via `go env -w`.")
+            .fix(|| {
+                let go = get_current_bin_dir()?.join(GO_BINARY);
+                exec(&[&go.to_string_lossy(), "env", "-u", GOTOOLCHAIN])?;
+                Ok("GOTOOLCHAIN has been unset via `go env -u`".into())
+            })),
+    }
+}
+
+const PROFILE: &str = "Profile";
+
+fn check_profile() -> Result<Finding> {
+    let shell = shell::get_shell();
+    let profile = shell.get_profile_dir()?;
+
+    if read_profile(&shell)?.contains(PROFILE_MARKER) {
+        return Ok(Finding::ok(PROFILE, profile.to_string_lossy()));
+    }
+
+    Ok(Finding::new(
+        PROFILE,
+        Severity::Warning,
+        format!("goup's environment variables are not applied in {}", profile.display()),
+    )
+    .hint("New terminal sessions will not use the selected SDK.")
+    .fix(move || {
+        apply_profile(&shell)?;
+        Ok("the environment variables have been added to the profile".into())
+    }))
+}
+
+fn is_same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_classify_toolchain() {
+        assert_eq!(Toolchain::Local, classify_toolchain("local"));
+        assert_eq!(Toolchain::Path, classify_toolchain("path"));
+        assert_eq!(Toolchain::Auto, classify_toolchain("auto"));
+        assert_eq!(Toolchain::Auto, classify_toolchain(""));
+        assert_eq!(Toolchain::Auto, classify_toolchain("local+auto"));
+        assert_eq!(Toolchain::Path, classify_toolchain("local+path"));
+        assert_eq!(
+            Toolchain::Forced("go1.21.0".into()),
+            classify_toolchain("go1.21.0")
+        );
+        assert_eq!(
+            Toolchain::Forced("go1.22.1".into()),
+            classify_toolchain("go1.22.1+auto")
+        );
+    }
+}
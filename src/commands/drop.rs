@@ -1,38 +1,37 @@
 use super::Command;
-use crate::{env::*, progress::Spinner, tui::*, versions::Version};
+use crate::{
+    env::*,
+    progress::Spinner,
+    resolve::{find_installed_version, find_version},
+    tui::*,
+};
 use clap::Args;
-use console::style;
 
 /// Drop an installed SDK.
 #[derive(Args)]
 #[command(visible_aliases = ["delete", "remove", "rm"])]
 pub struct Drop {
-    /// The version which should be dropped.
+    /// The version or alias which should be dropped.
     version: String,
 }
 
 impl Command for Drop {
     fn run(&self) -> anyhow::Result<()> {
-        let target: Version = self.version.parse()?;
-
-        let versions = get_installed_versions()?;
-        let versions: Vec<_> = versions.iter().filter(|v| target.covers(v)).collect();
-
-        if versions.is_empty() {
-            anyhow::bail!("No SDK found matching the given version.");
+        let target = &match find_installed_version(&self.version) {
+            Ok(v) => v,
+            Err(err) => match find_version(&self.version, &get_system_versions()?) {
+                Ok(v) => anyhow::bail!(
+                    "SDK {v} is provided by the system store and can not be dropped. \
+                    Administrators can remove it using `goup --system drop {v}`."
+                ),
+                Err(_) => return Err(err),
+            },
+        };
+
+        if is_pinned(target)? {
+            anyhow::bail!("SDK {target} is pinned. Use `goup unpin {target}` to unpin it first.");
         }
 
-        if versions.len() > 1 {
-            let v: Vec<_> = versions.iter().map(|v| v.to_string()).collect();
-            anyhow::bail!(
-                "The given version matches multiple SDKs. Please supply a less ambigious version.\n\n{}\n{}",
-                style("Matching versions:").underlined(),
-                style(v.join("\n")).red()
-            );
-        }
-
-        let target = versions[0];
-
         let current = get_current_version()?;
         let is_current = matches!(current, Some(c) if &c == target);
         if is_current
@@ -46,13 +45,13 @@ impl Command for Drop {
         }
 
         if is_current {
-            link_current_version(None)?;
-            write_current_version(None)?;
+            set_current_version(None)?;
         }
 
         {
             Spinner::new("Removing SDK ...");
             drop_version(target)?;
+            sync_versioned_binaries()?;
         }
 
         print_success("SDK has been removed.");
@@ -0,0 +1,50 @@
+use super::Command;
+use crate::{env::*, warning};
+use clap::Args;
+use console::style;
+use indicatif::HumanBytes;
+
+/// Display the disk usage of installed SDKs.
+///
+/// Files shared between SDKs via hard links *(see `goup dedup`)*
+/// are counted for each SDK, but only once for the total. The
+/// exclusive size is freed when dropping an SDK.
+#[derive(Args)]
+pub struct Du {}
+
+impl Command for Du {
+    fn run(&self) -> anyhow::Result<()> {
+        let (usage, total) = get_disk_usage()?;
+        if usage.is_empty() {
+            warning!("No SDKs installed.");
+            return Ok(());
+        }
+
+        for sdk in &usage {
+            println!(
+                "{:<12} {:>11}  {}",
+                sdk.version.to_string(),
+                HumanBytes(sdk.size).to_string(),
+                style(format!("({} exclusive)", HumanBytes(sdk.exclusive))).dim()
+            );
+        }
+
+        let size: u64 = usage.iter().map(|sdk| sdk.size).sum();
+        println!(
+            "{:<12} {:>11}  {}",
+            style("total").bold(),
+            HumanBytes(total).to_string(),
+            style(format!(
+                "({} saved by hard links)",
+                HumanBytes(size.saturating_sub(total))
+            ))
+            .dim()
+        );
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
@@ -1,6 +1,6 @@
 use super::Command;
 use crate::{
-    env::{self, get_env_vars},
+    env::{self, get_env_vars, LockMode},
     shell::{self, ShellEnv},
     success, warning,
 };
@@ -9,7 +9,7 @@ use clap::Args;
 use console::style;
 use whattheshell::Shell;
 
-const PROFILE_MARKER: &str = "# goup:envvars";
+pub(super) const PROFILE_MARKER: &str = "# goup:envvars";
 
 fn get_long_about() -> String {
     let shell = shell::get_shell();
@@ -52,9 +52,15 @@ impl Command for Env {
 
         Ok(())
     }
+
+    fn lock_mode(&self) -> LockMode {
+        // Printing the environment variables is used in shell
+        // profiles and must never wait for other goup processes.
+        LockMode::None
+    }
 }
 
-fn apply_profile(shell: &Shell) -> Result<()> {
+pub(super) fn apply_profile(shell: &Shell) -> Result<()> {
     let profile_content = env::read_profile(shell)?;
     if profile_content.contains(PROFILE_MARKER) {
         warning!(
@@ -0,0 +1,51 @@
+use super::Command;
+use crate::{env::*, warning};
+use clap::Args;
+use console::style;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Display the history of selected SDKs.
+#[derive(Args)]
+pub struct History {
+    /// Number of latest entries to display.
+    #[arg(short = 'n', long, default_value_t = 20, conflicts_with = "all")]
+    limit: usize,
+
+    /// Display all entries.
+    #[arg(short, long)]
+    all: bool,
+}
+
+impl Command for History {
+    fn run(&self) -> anyhow::Result<()> {
+        let history = read_history()?;
+        if history.is_empty() {
+            warning!("No SDK has been selected yet.");
+            return Ok(());
+        }
+
+        let skip = if self.all {
+            0
+        } else {
+            history.len().saturating_sub(self.limit)
+        };
+
+        for entry in &history[skip..] {
+            let time = UNIX_EPOCH + Duration::from_secs(entry.time);
+            println!(
+                "{}  {:<10} {} {:<10} {}",
+                style(humantime::format_rfc3339_seconds(time)).dim(),
+                entry.from.as_deref().unwrap_or("-"),
+                style("→").dim(),
+                entry.to.as_deref().unwrap_or("-"),
+                style(format!("(goup {})", entry.command)).dim()
+            );
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
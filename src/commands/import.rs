@@ -0,0 +1,182 @@
+use super::Command;
+use crate::{
+    env::*,
+    managers::{Installation, Manager},
+    success,
+    tui::print_note,
+    versions::Version,
+    warning,
+};
+use clap::Args;
+use console::style;
+
+/// The outcome of importing a single installation.
+enum Outcome {
+    Imported(Version),
+    Skipped(Version, String),
+    Failed(String),
+}
+
+/// Import SDKs installed by other version managers.
+#[derive(Args)]
+pub struct Import {
+    /// The version manager to import SDKs from.
+    #[arg(value_enum, short, long)]
+    from: Manager,
+
+    /// How SDKs are transferred into goup's installations directory.
+    #[arg(value_enum, short, long, default_value_t = AdoptMode::Copy)]
+    mode: AdoptMode,
+
+    /// Do not select the SDK currently selected in the
+    /// other version manager.
+    #[arg(long)]
+    keep_selection: bool,
+
+    /// Only print which SDKs would be imported.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Command for Import {
+    fn run(&self) -> anyhow::Result<()> {
+        let installations = self.from.discover()?;
+        if installations.is_empty() {
+            warning!(
+                "No SDKs of {} found in {}.",
+                self.from,
+                self.from.root()?.to_string_lossy()
+            );
+            return Ok(());
+        }
+
+        let mut installed = get_installed_versions()?;
+
+        println!("{}", style(format!("Migration report ({}):", self.from)).bold());
+
+        let mut imported = vec![];
+        for installation in &installations {
+            let outcome = self.import(installation, &installed);
+
+            match &outcome {
+                Outcome::Imported(v) => {
+                    println!(
+                        "  {:<10} {:<12} {}",
+                        v.to_string(),
+                        style(if self.dry_run { "would import" } else { "imported" }).green(),
+                        style(installation.goroot.to_string_lossy()).dim()
+                    );
+                    installed.push(v.clone());
+                }
+                Outcome::Skipped(v, reason) => {
+                    println!("  {:<10} {:<12} {}", v.to_string(), style("skipped").yellow(), reason)
+                }
+                Outcome::Failed(reason) => println!(
+                    "  {:<10} {:<12} {}",
+                    installation.name,
+                    style("failed").red(),
+                    reason
+                ),
+            }
+
+            imported.push((installation, outcome));
+        }
+
+        let n_imported = imported
+            .iter()
+            .filter(|(_, o)| matches!(o, Outcome::Imported(_)))
+            .count();
+
+        println!();
+
+        if !self.dry_run && n_imported > 0 {
+            sync_versioned_binaries()?;
+        }
+
+        if !self.keep_selection {
+            self.carry_over_selection(&imported)?;
+        }
+
+        if self.dry_run {
+            print_note(&format!("{n_imported} SDK(s) would be imported."));
+        } else {
+            success!("{n_imported} SDK(s) have been imported.");
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+impl Import {
+    fn import(&self, installation: &Installation, installed: &[Version]) -> Outcome {
+        let version = match get_goroot_version(&installation.goroot) {
+            Ok(v) => v,
+            Err(err) => return Outcome::Failed(format!("invalid SDK: {err}")),
+        };
+
+        if installed.contains(&version) {
+            return Outcome::Skipped(version, "already installed".into());
+        }
+
+        if let Err(err) = check_goroot(&installation.goroot, &version) {
+            return Outcome::Failed(format!("invalid SDK: {err}"));
+        }
+
+        if !self.dry_run {
+            if let Err(err) = adopt_sdk(&installation.goroot, &version, self.mode) {
+                return Outcome::Failed(err.to_string());
+            }
+        }
+
+        Outcome::Imported(version)
+    }
+
+    /// Selects the SDK which is currently selected in the
+    /// other version manager, if it is available in goup.
+    fn carry_over_selection(&self, imported: &[(&Installation, Outcome)]) -> anyhow::Result<()> {
+        let Some(current) = self.from.current()? else {
+            return Ok(());
+        };
+
+        let version = imported
+            .iter()
+            .find(|(i, _)| i.name == current)
+            .and_then(|(_, o)| match o {
+                Outcome::Imported(v) | Outcome::Skipped(v, _) => Some(v),
+                Outcome::Failed(_) => None,
+            });
+
+        let Some(version) = version else {
+            warning!(
+                "The SDK {current} selected in {} could not be imported and is not selected.",
+                self.from
+            );
+            return Ok(());
+        };
+
+        if get_current_version()?.as_ref() == Some(version) {
+            return Ok(());
+        }
+
+        if self.dry_run {
+            print_note(&format!(
+                "SDK {version} would be selected as it is the current version in {}.",
+                self.from
+            ));
+            return Ok(());
+        }
+
+        set_current_version(Some(version))?;
+
+        print_note(&format!(
+            "Selected SDK {version} as it is the current version in {}.",
+            self.from
+        ));
+
+        Ok(())
+    }
+}
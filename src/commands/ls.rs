@@ -1,18 +1,31 @@
 use super::Command;
-use crate::{env::*, shell, warning};
+use crate::{
+    env::*,
+    profile,
+    resolve::get_alias_installed_target,
+    shell,
+    versions::{get_cached_upstream_versions, get_unsupported_since},
+    warning,
+};
 use clap::Args;
 use console::style;
+use std::time::{Duration, UNIX_EPOCH};
 
-/// Display currently installed SDKs.
+/// Display currently installed SDKs, including those
+/// provided by the system store.
 #[derive(Args)]
 #[command(visible_aliases = ["list"])]
-pub struct Ls {}
+pub struct Ls {
+    /// Display how and when each SDK has been installed.
+    #[arg(short, long)]
+    long: bool,
+}
 
 impl Command for Ls {
     fn run(&self) -> anyhow::Result<()> {
         check_env_applied(&shell::get_shell())?;
 
-        let mut versions = get_installed_versions()?;
+        let mut versions = get_available_versions()?;
         if versions.is_empty() {
             warning!("There are no versions currently installed.");
             return Ok(());
@@ -21,17 +34,93 @@ impl Command for Ls {
         versions.sort();
 
         let current = get_current_version()?;
+        let state = State::load()?;
+        let system_state = load_system_state()?.unwrap_or_default();
+        let installed = get_installed_versions()?;
+        let upstream_versions = get_cached_upstream_versions().unwrap_or_default();
+
+        for v in &versions {
+            let is_system = !installed.contains(v);
+            let sdk = match is_system {
+                true => system_state.sdk(v),
+                false => state.sdk(v),
+            };
+
+            let names: Vec<_> = state
+                .aliases
+                .iter()
+                .filter(|(_, target)| get_alias_installed_target(target, &versions) == Some(v))
+                .map(|(name, _)| name.as_str())
+                .collect();
+            let names = if names.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", style(format!("({})", names.join(", "))).dim())
+            };
+            let names = match get_unsupported_since(&upstream_versions, v) {
+                Some(since) => format!(
+                    "{names} {}",
+                    style(format!("(unsupported since {since})")).yellow()
+                ),
+                None => names,
+            };
+            let names = match sdk.filter(|sdk| sdk.pinned && !is_system) {
+                Some(_) => format!("{names} {}", style("(pinned)").cyan()),
+                None => names,
+            };
+            let names = match is_system {
+                true => format!("{names} {}", style("(system)").blue()),
+                false => names,
+            };
+            let names = match sdk.and_then(format_profile) {
+                Some(profile) => format!("{names} {}", style(profile).magenta()),
+                None => names,
+            };
+            let names = match sdk.filter(|_| self.long) {
+                Some(sdk) => format!("{names} {}", style(format_details(sdk)).dim()),
+                None => names,
+            };
 
-        for v in versions {
             if let Some(c) = &current {
-                if c == &v {
-                    println!("{}", style(format!("* {v}")).green().bold());
+                if c == v {
+                    println!("{}{names}", style(format!("* {v}")).green().bold());
                     continue;
                 }
             }
-            println!("  {v}");
+            println!("  {v}{names}");
         }
 
         Ok(())
     }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+/// Formats the install profile of the given SDK, if not all
+/// files have been installed. The patterns of custom profiles
+/// are listed *(like `(custom: without go/doc, go/test)`)*.
+fn format_profile(sdk: &Sdk) -> Option<String> {
+    let profile = sdk.profile.as_ref()?;
+    if profile != profile::CUSTOM || sdk.excluded.is_empty() {
+        return Some(format!("({profile})"));
+    }
+
+    let mut details = format!("without {}", sdk.excluded.join(", "));
+    if !sdk.included.is_empty() {
+        details += &format!(" except {}", sdk.included.join(", "));
+    }
+    Some(format!("({profile}: {details})"))
+}
+
+/// Formats the installation details of the given SDK
+/// *(like `[downloaded, installed 2024-05-07T10:12:43Z]`)*.
+fn format_details(sdk: &Sdk) -> String {
+    let installed_at = UNIX_EPOCH + Duration::from_secs(sdk.installed_at);
+    format!(
+        "[{}, installed {}]",
+        sdk.origin,
+        humantime::format_rfc3339_seconds(installed_at)
+    )
 }
@@ -1,5 +1,5 @@
 use super::Command;
-use crate::versions::get_upstream_versions;
+use crate::{env::LockMode, versions::get_upstream_versions};
 use clap::{Args, ValueEnum};
 
 #[derive(ValueEnum, Clone)]
@@ -34,4 +34,8 @@ impl Command for Lsr {
 
         Ok(())
     }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
 }
@@ -1,5 +1,6 @@
 crate::prelude! {
     current
+    doctor
     env
     ls
     lsr
@@ -7,11 +8,41 @@ crate::prelude! {
     drop
     clean
     check
+    build
+    import
+    toolchains
+    alias
+    prune
+    relocate
+    update
+    config
+    history
+    pin
+    verify
+    reinstall
+    du
+    dedup
 }
 
+use crate::env::LockMode;
 use anyhow::Result;
 
 /// Definition of an executable CLI sub command.
 pub trait Command {
     fn run(&self) -> Result<()>;
+
+    /// Returns how the working directory is locked against
+    /// other goup processes while the command runs.
+    ///
+    /// Commands lock exclusively by default. Commands which do
+    /// not remove SDKs should lock shared instead.
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Exclusive
+    }
 }
+
+/// Error which can be returned by a [`Command`] to exit
+/// with the given exit code without printing an error.
+#[derive(Debug, thiserror::Error)]
+#[error("exit code {0}")]
+pub struct Exit(pub i32);
@@ -0,0 +1,76 @@
+use super::Command;
+use crate::{env::*, resolve::find_version, success, versions::Version};
+use clap::Args;
+
+const LONG_ABOUT: &str = "\
+Pin an installed SDK.
+
+Pinned SDKs are never removed by `clean`, `prune` or `update` and can \
+only be dropped after unpinning them. When the selected SDK is pinned, \
+`goup use` without arguments and `goup update` keep the selection, so it \
+only changes when a version is passed to `goup use` explicitly.
+
+If no version is passed, the currently selected SDK is pinned.";
+
+/// Pin an SDK to protect it from removal and upgrades.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Pin {
+    /// The version or alias of the SDK to pin.
+    version: Option<String>,
+}
+
+/// Unpin a previously pinned SDK.
+#[derive(Args)]
+pub struct Unpin {
+    /// The version or alias of the SDK to unpin. Defaults
+    /// to the currently selected SDK.
+    version: Option<String>,
+}
+
+impl Command for Pin {
+    fn run(&self) -> anyhow::Result<()> {
+        let version = get_target(self.version.as_deref())?;
+        set_pinned(&version, true)?;
+        success!("SDK {version} has been pinned.");
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+impl Command for Unpin {
+    fn run(&self) -> anyhow::Result<()> {
+        let version = get_target(self.version.as_deref())?;
+        set_pinned(&version, false)?;
+        success!("SDK {version} has been unpinned.");
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+/// Returns the installed SDK matching the given version or,
+/// if not passed, the currently selected SDK.
+///
+/// SDKs provided by the system store can not be pinned, as
+/// they are managed by administrators.
+fn get_target(version: Option<&str>) -> anyhow::Result<Version> {
+    let version = match version {
+        Some(v) => find_version(v, &get_available_versions()?)?,
+        None => get_current_version()?
+            .ok_or_else(|| anyhow::anyhow!("No SDK is currently selected. Please pass a version."))?,
+    };
+
+    if is_system_version(&version)? {
+        anyhow::bail!(
+            "SDK {version} is provided by the system store and can not be pinned or unpinned."
+        );
+    }
+
+    Ok(version)
+}
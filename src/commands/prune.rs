@@ -0,0 +1,97 @@
+use super::Command;
+use crate::{
+    config::PRUNE_AUTO,
+    prune::{get_auto_prune_policy, prune_installed, PrunePolicy},
+    success,
+    tui::print_note,
+};
+use clap::Args;
+use console::style;
+
+fn get_long_about() -> String {
+    format!(
+        "Remove superseded SDKs according to retention policies.\n\n\
+        Retention rules can either be passed as flags or as a comma separated \
+        policy like `keep-latest-patch,keep-minors=3`. The currently selected \
+        SDK and SDKs which are no upstream releases are never removed.\n\n\
+        If a policy is configured as `{PRUNE_AUTO}` *(see `goup help config`)*, it \
+        is used when no rules are passed and it is applied automatically after \
+        installing a new SDK."
+    )
+}
+
+/// Remove superseded SDKs.
+#[derive(Args)]
+#[command(long_about = get_long_about())]
+pub struct Prune {
+    /// Keep only the latest patch release of each minor version.
+    #[arg(long)]
+    keep_latest_patch: bool,
+
+    /// Keep only SDKs of the N latest minor versions.
+    #[arg(long, value_name = "N")]
+    keep_minors: Option<usize>,
+
+    /// Remove pre-releases when a stable release of the
+    /// same minor version is installed.
+    #[arg(long)]
+    drop_superseded_pre: bool,
+
+    /// Remove SDKs which have not been used for the given
+    /// number of days.
+    #[arg(long, value_name = "DAYS")]
+    unused_days: Option<u64>,
+
+    /// Retention rules as comma separated policy.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["keep_latest_patch", "keep_minors", "drop_superseded_pre", "unused_days"]
+    )]
+    policy: Option<PrunePolicy>,
+
+    /// Only print which SDKs would be removed.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Command for Prune {
+    fn run(&self) -> anyhow::Result<()> {
+        let policy = match &self.policy {
+            Some(p) => p.clone(),
+            None => PrunePolicy {
+                keep_latest_patch: self.keep_latest_patch,
+                keep_minors: self.keep_minors,
+                drop_superseded_pre: self.drop_superseded_pre,
+                unused_days: self.unused_days,
+            },
+        };
+
+        let policy = match policy {
+            p if p.is_empty() => get_auto_prune_policy()?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No retention rules have been passed. See `goup help prune` for more information."
+                )
+            })?,
+            p => p,
+        };
+
+        let pruned = prune_installed(&policy, self.dry_run)?;
+        if pruned.is_empty() {
+            success!("No SDKs need to be removed.");
+            return Ok(());
+        }
+
+        for (v, reason) in &pruned {
+            println!("  {:<10} {}", v.to_string(), style(reason).dim());
+        }
+
+        if self.dry_run {
+            print_note(&format!("{} SDK(s) would be removed.", pruned.len()));
+        } else {
+            success!("{} SDK(s) have been removed.", pruned.len());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use super::Command;
+use crate::{
+    env::{download::reinstall_version, *},
+    profile::{self, InstallProfile},
+    resolve::find_installed_version,
+    success,
+};
+use clap::Args;
+
+/// Download an installed SDK again and replace it.
+///
+/// The fresh copy is swapped in only after it has been
+/// downloaded completely. The selection and the pin status
+/// of the SDK are kept.
+#[derive(Args)]
+pub struct Reinstall {
+    /// The version or alias of the SDK to reinstall.
+    version: String,
+
+    /// Files to install. Defaults to the profile the SDK
+    /// has been installed with.
+    #[arg(long, value_parser = [profile::FULL, profile::MINIMAL, profile::CUSTOM])]
+    profile: Option<String>,
+}
+
+impl Command for Reinstall {
+    fn run(&self) -> anyhow::Result<()> {
+        let version = find_installed_version(&self.version)?;
+
+        let origin = State::load()?.sdk(&version).map(|sdk| sdk.origin);
+        if version.is_ident() || origin.is_some_and(|o| o != Origin::Downloaded) {
+            anyhow::bail!(
+                "SDK {version} has not been downloaded and can not be reinstalled. \
+                Drop it and build, link or import it again instead."
+            );
+        }
+
+        let profile = match &self.profile {
+            Some(name) => InstallProfile::new(name)?,
+            None => get_sdk_profile(&version)?,
+        };
+
+        reinstall_version(&version, &profile)?;
+        sync_versioned_binaries()?;
+
+        success!("SDK {version} has been reinstalled.");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use super::Command;
+use crate::{
+    config::{self, HOME_ENV},
+    env::*,
+    progress::Spinner,
+    shell::{get_shell, ShellEnv},
+    success,
+    tui::print_note,
+    warning,
+};
+use clap::Args;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const LONG_ABOUT: &str = "\
+Move goup's working directory to another location.
+
+All installed SDKs and goup's state are moved into the given directory, \
+which is then set as `home` in the configuration. The symlink to the \
+currently selected SDK, the versioned launchers and references to the \
+old location in your profile file are updated accordingly.";
+
+/// Move installed SDKs to another directory.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Relocate {
+    /// The new working directory.
+    dir: PathBuf,
+
+    /// Do not update references to the old location
+    /// in the profile file.
+    #[arg(long)]
+    no_profile: bool,
+}
+
+impl Command for Relocate {
+    fn run(&self) -> anyhow::Result<()> {
+        let old_dir = get_work_dir()?;
+        let old_state_dir = get_state_dir()?;
+        let new_dir = std::path::absolute(&self.dir)?;
+
+        if new_dir == old_dir {
+            anyhow::bail!("goup's working directory already is {}.", old_dir.display());
+        }
+
+        if new_dir.starts_with(&old_dir) {
+            anyhow::bail!("The new directory must not be inside of the current working directory.");
+        }
+
+        if fs::read_dir(&new_dir).is_ok_and(|mut d| d.next().is_some()) {
+            anyhow::bail!("The directory {} is not empty.", new_dir.display());
+        }
+
+        let current = get_current_version()?;
+        let overridden = std::env::var_os(HOME_ENV).is_some();
+
+        let state_res = {
+            Spinner::new(format!("Moving SDKs to {} ...", new_dir.display()));
+
+            // The symlink is removed before moving, because it would
+            // be followed when the directory needs to be copied.
+            link_current_version(None)?;
+
+            if let Err(err) = move_work_dir(&old_dir, &new_dir) {
+                // The SDKs are still at the old location, as the old
+                // directory is only removed after a complete copy.
+                _ = link_current_version(current.as_ref());
+                return Err(err);
+            }
+
+            // When using the XDG layout, state files are stored
+            // separately and are moved into the new directory.
+            match old_state_dir != old_dir && old_state_dir.exists() {
+                true => move_state_files(&old_state_dir, &new_dir),
+                false => Ok(()),
+            }
+        };
+
+        let mut config = config::Config::load()?;
+        config.set(config::HOME, &new_dir.to_string_lossy())?;
+        config.save()?;
+
+        // Resolve all directories relative to the new location
+        // for the remainder of this process.
+        std::env::set_var(HOME_ENV, &new_dir);
+
+        link_current_version(current.as_ref())?;
+        sync_versioned_binaries()?;
+
+        if let Err(err) = state_res {
+            anyhow::bail!(
+                "SDKs have been moved to {}, but moving the state files failed: {err}\n\
+                Please move the remaining files in {} manually.",
+                new_dir.display(),
+                old_state_dir.display()
+            );
+        }
+
+        success!("SDKs have been moved to {}.", new_dir.display());
+
+        if !self.no_profile {
+            update_profile(&old_dir, &new_dir)?;
+        }
+
+        if overridden {
+            warning!(
+                "The working directory is overridden by {HOME_ENV} or `--home`. \
+                Please update or remove it to use the new location."
+            );
+        }
+
+        print_note(&format!(
+            "Apply the updated environment variables to your current terminal session using `{}`.",
+            get_shell().get_apply_env_command()?
+        ));
+
+        Ok(())
+    }
+}
+
+/// Moves the working directory `old_dir` to `new_dir`, which
+/// must be empty if it exists. If moving fails, a partial copy
+/// in `new_dir` is removed again, leaving it empty.
+fn move_work_dir(old_dir: &Path, new_dir: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = new_dir.parent() {
+        ensure_dir(parent)?;
+    }
+
+    if !old_dir.exists() {
+        return ensure_dir(new_dir);
+    }
+
+    let existed = new_dir.exists();
+    let res = move_dir(old_dir, new_dir);
+    if res.is_err() && existed {
+        _ = fs::remove_dir_all(new_dir);
+        _ = fs::create_dir(new_dir);
+    }
+
+    res
+}
+
+/// Moves all state files in `state_dir` into `new_dir`.
+fn move_state_files(state_dir: &Path, new_dir: &Path) -> anyhow::Result<()> {
+    for entry in fs::read_dir(state_dir)? {
+        let entry = entry?;
+        let target = new_dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_dir(entry.path(), target)?;
+        } else if fs::rename(entry.path(), &target).is_err() {
+            fs::copy(entry.path(), &target)?;
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces all references to `old_dir` in the profile file
+/// with `new_dir`.
+fn update_profile(old_dir: &Path, new_dir: &Path) -> anyhow::Result<()> {
+    let shell = get_shell();
+    let content = read_profile(&shell)?;
+
+    let old = old_dir.to_string_lossy();
+    if !content.contains(old.as_ref()) {
+        return Ok(());
+    }
+
+    write_profile(&shell, &content.replace(old.as_ref(), &new_dir.to_string_lossy()))?;
+    print_note("References to the old location in your profile file have been updated.");
+
+    Ok(())
+}
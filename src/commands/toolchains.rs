@@ -0,0 +1,180 @@
+use super::Command;
+use crate::{
+    env::*,
+    modcache::{get_modcache_dir, get_toolchains, Toolchain},
+    progress::Spinner,
+    success,
+    tui::print_note,
+    versions::Version,
+    warning,
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use console::style;
+use std::path::Path;
+
+const LONG_ABOUT: &str = "\
+Manage toolchains downloaded into the Go module cache.
+
+Since Go 1.21, the Go command downloads toolchains into the module cache \
+when a module requires a newer Go version than the one installed \
+(`GOTOOLCHAIN=auto`). These toolchains can be adopted into goup's \
+installations so they can be selected with `goup use`.";
+
+/// Manage toolchains in the Go module cache.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Toolchains {
+    #[command(subcommand)]
+    command: ToolchainsCommands,
+}
+
+#[derive(Subcommand)]
+enum ToolchainsCommands {
+    /// List toolchains in the Go module cache.
+    #[command(visible_aliases = ["list"])]
+    Ls,
+
+    /// Adopt toolchains from the Go module cache as installed SDKs.
+    Adopt {
+        /// Only adopt toolchains matching the given version.
+        version: Option<String>,
+
+        /// Remove the toolchains from the module cache after adopting.
+        #[arg(short, long)]
+        clean: bool,
+    },
+
+    /// Remove toolchains from the Go module cache.
+    #[command(visible_aliases = ["rm"])]
+    Clean {
+        /// Only remove toolchains matching the given version.
+        version: Option<String>,
+    },
+}
+
+impl Command for Toolchains {
+    fn run(&self) -> anyhow::Result<()> {
+        let modcache = get_modcache_dir()?;
+
+        match &self.command {
+            ToolchainsCommands::Ls => list(&modcache),
+            ToolchainsCommands::Adopt { version, clean } => {
+                adopt(&modcache, version.as_deref(), *clean)
+            }
+            ToolchainsCommands::Clean { version } => clean(&modcache, version.as_deref()),
+        }
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+fn list(modcache: &Path) -> Result<()> {
+    let toolchains = get_toolchains(modcache)?;
+    if toolchains.is_empty() {
+        warning!(
+            "There are no toolchains in the module cache ({}).",
+            modcache.to_string_lossy()
+        );
+        return Ok(());
+    }
+
+    let installed = get_installed_versions()?;
+
+    for tc in toolchains {
+        let status = if !tc.is_native() {
+            style("foreign platform").dim()
+        } else if installed.contains(&tc.version) {
+            style("installed").green()
+        } else {
+            style("not adopted").yellow()
+        };
+
+        println!(
+            "{:<10} {:<14} {}",
+            tc.version.to_string(),
+            tc.platform,
+            status
+        );
+    }
+
+    Ok(())
+}
+
+fn adopt(modcache: &Path, version: Option<&str>, clean: bool) -> Result<()> {
+    let toolchains = get_matching_toolchains(modcache, version)?;
+    let installed = get_installed_versions()?;
+
+    let mut adopted = 0;
+    let mut removed = 0;
+    for tc in toolchains.iter().filter(|tc| tc.is_native()) {
+        if installed.contains(&tc.version) {
+            print_note(&format!("Toolchain {} is already installed.", tc.version));
+        } else {
+            check_goroot(&tc.dir, &tc.version).map_err(|err| {
+                anyhow::anyhow!("Toolchain {} is not a valid SDK: {err}", tc.version)
+            })?;
+
+            {
+                Spinner::new(format!("Adopting toolchain {} ...", tc.version));
+                adopt_sdk(&tc.dir, &tc.version, AdoptMode::Copy)?;
+                make_writable_all(get_version_installation_dir(&tc.version)?)?;
+            }
+
+            success!("Toolchain {} has been adopted.", tc.version);
+            adopted += 1;
+        }
+
+        if clean {
+            tc.remove(modcache)?;
+            removed += 1;
+        }
+    }
+
+    if adopted == 0 {
+        warning!("No toolchains have been adopted.");
+    } else {
+        sync_versioned_binaries()?;
+    }
+
+    if removed > 0 {
+        success!("{removed} toolchain(s) have been removed from the module cache.");
+    }
+
+    Ok(())
+}
+
+fn clean(modcache: &Path, version: Option<&str>) -> Result<()> {
+    let toolchains = get_matching_toolchains(modcache, version)?;
+
+    {
+        Spinner::new("Removing toolchains ...");
+        for tc in &toolchains {
+            tc.remove(modcache)?;
+        }
+    }
+
+    success!(
+        "{} toolchain(s) have been removed from the module cache.",
+        toolchains.len()
+    );
+
+    Ok(())
+}
+
+fn get_matching_toolchains(modcache: &Path, version: Option<&str>) -> Result<Vec<Toolchain>> {
+    let target: Option<Version> = version.map(|v| v.parse()).transpose()?;
+
+    let toolchains: Vec<_> = get_toolchains(modcache)?
+        .into_iter()
+        .filter(|tc| target.as_ref().is_none_or(|t| t.covers(&tc.version)))
+        .collect();
+
+    if toolchains.is_empty() {
+        anyhow::bail!("No matching toolchains found in the module cache.");
+    }
+
+    Ok(toolchains)
+}
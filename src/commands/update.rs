@@ -0,0 +1,317 @@
+use super::Command;
+use crate::{
+    env::{download::install_version, *},
+    error,
+    policy::{get_policy, Policy},
+    profile::{get_install_profile, InstallProfile},
+    progress::Spinner,
+    prune::{get_auto_prune_policy, prune_installed},
+    shell, success,
+    tui::print_note,
+    versions::*,
+    warning,
+};
+use clap::Args;
+use console::style;
+use std::collections::BTreeMap;
+
+/// Update all installed minor versions to their latest patch.
+#[derive(Args)]
+#[command(long_about = "\
+Update all installed minor versions to their latest patch release.
+
+For each minor version of which a stable SDK is installed, the latest \
+upstream patch release is installed. If the currently selected SDK is \
+part of an updated minor version, the selection is moved to the new SDK. \
+SDKs provided by the system store are taken into account, but never removed.
+
+If the selection tracks a release channel *(see `goup help use`)*, it is \
+moved to the latest release of the channel instead. A pinned selection \
+*(see `goup help pin`)* is never moved and pinned SDKs are never removed.")]
+pub struct Update {
+    /// Remove SDKs which have been superseded by an update.
+    #[arg(short, long)]
+    remove_superseded: bool,
+}
+
+enum Outcome {
+    UpToDate,
+    Updated(Version),
+    Failed(Version),
+}
+
+impl Command for Update {
+    fn run(&self) -> anyhow::Result<()> {
+        check_env_applied(&shell::get_shell())?;
+
+        // SDKs of the system store are updated as well, but only
+        // SDKs installed by the user are removed.
+        let installed = get_installed_versions()?;
+        let available = get_available_versions()?;
+
+        let lines = get_lines(&available);
+        if lines.is_empty() {
+            warning!("There are no stable SDKs installed which could be updated.");
+            return Ok(());
+        }
+
+        let upstream_versions = {
+            Spinner::new("Checking for updates ...");
+            get_upstream_versions()?
+        };
+
+        let current = get_current_version()?;
+        let channel = get_current_channel()?;
+        let pinned = get_pinned_versions()?;
+        let keep_selection = current.as_ref().is_some_and(|c| pinned.contains(c));
+        let mut outcomes = vec![];
+        let mut drop_errs = vec![];
+
+        for (minor, latest) in &lines {
+            let Some(new) = get_new_patch(&upstream_versions, latest) else {
+                outcomes.push((minor, *latest, Outcome::UpToDate));
+                continue;
+            };
+
+            // Updates are installed with the profile of the SDK they
+            // supersede, so minimal installs stay minimal.
+            if let Err(err) = get_policy()?
+                .enforce(new, &upstream_versions)
+                .and_then(|_| get_update_profile(latest, &installed))
+                .and_then(|profile| install_version(new, &profile))
+            {
+                error!("Installing SDK {new} failed: {err}");
+                outcomes.push((minor, *latest, Outcome::Failed(new.clone())));
+                continue;
+            }
+
+            // When the selection tracks a channel, it is moved
+            // within the channel after all lines have been updated.
+            if channel.is_none()
+                && !keep_selection
+                && current.as_ref().is_some_and(|c| minor.covers(c))
+            {
+                set_current_version(Some(new))?;
+                print_note(&format!("Switched to SDK version {new}."));
+            }
+
+            if self.remove_superseded {
+                for v in get_superseded(&installed, minor, new, &pinned) {
+                    if let Err(err) = drop_version(v) {
+                        drop_errs.push(format!("- {v}: {err}"));
+                    }
+                }
+            }
+
+            outcomes.push((minor, *latest, Outcome::Updated(new.clone())));
+        }
+
+        let mut channel_moved = false;
+        let mut channel_installed = false;
+        if let Some(channel) = channel.as_ref().filter(|_| !keep_selection) {
+            if let Some(new) = select_channel_version(channel, &upstream_versions, get_policy()?)? {
+                if current.as_ref() != Some(new) {
+                    if !is_installed(new)? && !is_system_version(new)? {
+                        let profile = match &current {
+                            Some(c) => get_update_profile(c, &installed)?,
+                            None => get_install_profile(None)?,
+                        };
+                        install_version(new, &profile)?;
+                        channel_installed = true;
+                    }
+                    set_current_version(Some(new))?;
+                    print_note(&format!("Switched to SDK version {new} (channel {channel})."));
+                    channel_moved = true;
+                }
+            }
+        }
+
+        sync_versioned_binaries()?;
+
+        if let Some(current) = current.as_ref().filter(|_| keep_selection) {
+            print_note(&format!("SDK {current} is pinned and stays selected."));
+        }
+
+        println!();
+        for (minor, latest, outcome) in &outcomes {
+            let minor = minor.to_string();
+            let latest = latest.to_string();
+            match outcome {
+                Outcome::UpToDate => println!(
+                    "  {minor:<6} {latest:<10}   {:<10} {}",
+                    "",
+                    style("up to date").dim()
+                ),
+                Outcome::Updated(new) => println!(
+                    "  {minor:<6} {latest:<10} → {:<10} {}",
+                    style(new.to_string()).cyan(),
+                    style("updated").green()
+                ),
+                Outcome::Failed(new) => println!(
+                    "  {minor:<6} {latest:<10} → {:<10} {}",
+                    new.to_string(),
+                    style("failed").red()
+                ),
+            }
+        }
+        println!();
+
+        let failed = outcomes
+            .iter()
+            .filter(|(_, _, o)| matches!(o, Outcome::Failed(..)))
+            .count();
+        if failed > 0 || !drop_errs.is_empty() {
+            let mut errs = vec![];
+            if failed > 0 {
+                errs.push(format!("{failed} SDK(s) could not be updated."));
+            }
+            if !drop_errs.is_empty() {
+                errs.push(format!(
+                    "Failed removing the following superseded versions:\n{}",
+                    drop_errs.join("\n")
+                ));
+            }
+            anyhow::bail!("{}", errs.join("\n"));
+        }
+
+        if !channel_moved
+            && outcomes
+                .iter()
+                .all(|(_, _, o)| matches!(o, Outcome::UpToDate))
+        {
+            success!("All installed SDKs are up to date!");
+            return Ok(());
+        }
+
+        success!("All installed SDKs have been updated!");
+
+        let installed_any = channel_installed
+            || outcomes
+                .iter()
+                .any(|(_, _, o)| matches!(o, Outcome::Updated(..)));
+        if let Some(policy) = get_auto_prune_policy()?.filter(|_| installed_any) {
+            for (v, reason) in prune_installed(&policy, false)? {
+                print_note(&format!("Removed SDK {v} ({reason})."));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the install profile for the update of the given
+/// superseded SDK, which is the profile it has been installed
+/// with. For SDKs of the system store, the configured profile
+/// is used.
+fn get_update_profile(superseded: &Version, installed: &[Version]) -> anyhow::Result<InstallProfile> {
+    match installed.contains(superseded) {
+        true => get_sdk_profile(superseded),
+        false => get_install_profile(None),
+    }
+}
+
+/// Returns the latest installed stable SDK of each minor
+/// version, by the minor version.
+fn get_lines(versions: &[Version]) -> BTreeMap<Version, &Version> {
+    let mut lines: BTreeMap<Version, &Version> = BTreeMap::new();
+    for v in versions.iter().filter(|v| v.is_stable()) {
+        let latest = lines.entry(v.strip_after(VersionPart::Minor)).or_insert(v);
+        if v > *latest {
+            *latest = v;
+        }
+    }
+    lines
+}
+
+/// Returns the installed SDKs of the given minor version which
+/// have been superseded by `new`. Pinned SDKs are never returned.
+fn get_superseded<'a>(
+    installed: &'a [Version],
+    minor: &Version,
+    new: &Version,
+    pinned: &[Version],
+) -> Vec<&'a Version> {
+    installed
+        .iter()
+        .filter(|v| v.is_stable() && minor.covers(v) && *v < new)
+        .filter(|v| !pinned.contains(v))
+        .collect()
+}
+
+/// Returns the latest upstream release of the given channel
+/// which is permitted by the policy.
+fn select_channel_version<'a>(
+    channel: &Channel,
+    upstream_versions: &'a [Version],
+    policy: &Policy,
+) -> anyhow::Result<Option<&'a Version>> {
+    let candidates = upstream_versions
+        .iter()
+        .rev()
+        .filter(|v| channel.contains(v));
+    policy.select(candidates, upstream_versions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn versions(v: &[&str]) -> Vec<Version> {
+        v.iter().map(|v| v.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_get_lines() {
+        let installed = versions(&["1.21.0", "1.21.3", "1.22.1", "1.22.0", "1.23rc1"]);
+        let lines = get_lines(&installed);
+
+        let lines: Vec<_> = lines
+            .iter()
+            .map(|(minor, latest)| (minor.to_string(), latest.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("1.21".to_string(), "1.21.3".to_string()),
+                ("1.22".to_string(), "1.22.1".to_string()),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn test_get_superseded() {
+        let installed = versions(&["1.21.0", "1.22.0", "1.22.1", "1.22.2", "1.23.0"]);
+        let minor = "1.22".parse().unwrap();
+        let new = "1.22.2".parse().unwrap();
+
+        assert_eq!(
+            versions(&["1.22.0", "1.22.1"]).iter().collect::<Vec<_>>(),
+            get_superseded(&installed, &minor, &new, &[])
+        );
+        assert_eq!(
+            versions(&["1.22.0"]).iter().collect::<Vec<_>>(),
+            get_superseded(&installed, &minor, &new, &versions(&["1.22.1"]))
+        );
+    }
+
+    #[test]
+    fn test_select_channel_version() {
+        let upstream = versions(&["1.21.0", "1.21.1", "1.22.0", "1.22.1", "1.23rc1"]);
+        let policy = Policy::default();
+        let select = |channel: &str, policy: &Policy| {
+            select_channel_version(&channel.parse().unwrap(), &upstream, policy)
+                .unwrap()
+                .map(ToString::to_string)
+        };
+
+        assert_eq!(Some("1.22.1".into()), select("stable", &policy));
+        assert_eq!(Some("1.23rc1".into()), select("unstable", &policy));
+        assert_eq!(Some("1.21.1".into()), select("1.21", &policy));
+
+        let policy = Policy {
+            banned: versions(&["1.22.1"]),
+            ..Policy::default()
+        };
+        assert_eq!(Some("1.22.0".into()), select("stable", &policy));
+    }
+}
@@ -1,77 +1,178 @@
 use super::Command;
 use crate::{
-    env::{download::get_download_url, *},
-    progress, shell,
-    tui::{print_status, print_success},
+    config::get_config,
+    env::{download::install_version, *},
+    policy::get_policy,
+    profile::{self, get_install_profile},
+    prune::{get_auto_prune_policy, prune_installed},
+    resolve::resolve_alias,
+    shell,
+    tui::{print_note, print_success},
     versions::*,
 };
 use clap::Args;
-use flate2::bufread::GzDecoder;
-use indicatif::ProgressDrawTarget;
-use std::io::{self, BufReader};
-use tar::Archive;
-use zip::read::ZipArchive;
 
 /// Install a version of Go.
 #[derive(Args)]
 #[command(visible_aliases = ["u", "up", "select", "install"])]
 pub struct Use {
-    /// Specify a specific version or select the latest
-    /// stable or unstable release.
+    /// Specify a specific version or alias or select the
+    /// latest stable or unstable release.
+    ///
+    /// When a release channel like `stable`, `unstable` or a
+    /// minor version (`1.22` or `1.22.x`) is passed, the channel
+    /// is remembered and the selection moves within it when
+    /// running `goup use` without arguments or `goup update`.
+    ///
+    /// Pass `-` to switch back to the previously selected SDK.
+    #[arg(allow_hyphen_values = true)]
     version: Option<String>,
+
+    /// Files to install, overriding the `install.profile`
+    /// configuration. `minimal` skips tests, docs and test data.
+    #[arg(long, value_parser = [profile::FULL, profile::MINIMAL, profile::CUSTOM])]
+    profile: Option<String>,
 }
 
 impl Command for Use {
     fn run(&self) -> anyhow::Result<()> {
         check_env_applied(&shell::get_shell())?;
 
-        let version_inpt = self.version.as_ref().map(|v| v.to_lowercase());
-        let version: Version = match version_inpt.as_deref() {
-            Some("stable") | Some("latest") | Some("s") => get_latest_upstream_version(false)?,
-            Some("unstable") | Some("rc") => get_latest_upstream_version(true)?,
-            Some(v) => find_upstream_version(&v.parse()?)?,
-            None => {
-                let current = get_current_version()?;
-                get_latest_upstream_version(current.is_some_and(|c| !c.is_stable()))?
+        if self.version.as_deref() == Some("-") {
+            return select_previous_version();
+        }
+
+        let current = get_current_version()?;
+        if let Some(current) = current.as_ref().filter(|_| self.version.is_none()) {
+            if is_pinned(current)? {
+                print_note(&format!(
+                    "SDK {current} is pinned and stays selected. Pass a version to switch explicitly."
+                ));
+                return Ok(());
             }
+        }
+
+        let profile = get_install_profile(self.profile.as_deref())?;
+        let version_inpt = self.version.as_deref().map(resolve_alias).transpose()?;
+        let channel = match version_inpt.as_deref() {
+            Some(v) => v.parse::<Channel>().ok(),
+            None => get_current_channel()?.or_else(|| get_config().channel()),
         };
 
-        let install_dir = get_version_installation_dir(&version)?;
+        let version: Version = match (channel.as_ref(), version_inpt.as_deref()) {
+            (Some(channel), _) => {
+                let upstream_versions = get_upstream_versions()?;
+                let candidates = upstream_versions
+                    .iter()
+                    .rev()
+                    .filter(|v| channel.contains(v));
+                get_policy()?
+                    .select(candidates, &upstream_versions)?
+                    .cloned()
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("no matching stable version found for channel {channel}")
+                    })?
+            }
+            (None, Some(v)) => {
+                let version: Version = v.parse()?;
+                if version.is_ident() {
+                    select_ident_version(&version)?;
+                    return Ok(());
+                }
+                find_upstream_version(&version)?
+            }
+            (None, None) => get_latest_upstream_version(
+                current.as_ref().is_some_and(|c| !c.is_stable()),
+            )?,
+        };
 
-        if !get_installed_versions()?.contains(&version) {
-            ensure_dir(&install_dir)?;
+        let install = !is_installed(&version)? && !is_system_version(&version)?;
+        if install {
+            install_version(&version, &profile)?;
+            sync_versioned_binaries()?;
+        } else if self.profile.is_some()
+            && is_installed(&version)?
+            && get_sdk_profile(&version)?.name != profile.name
+        {
+            print_note(&format!(
+                "SDK {version} is already installed with another profile. \
+                Use `goup reinstall {version} --profile {}` to change it.",
+                profile.name
+            ));
+        }
 
-            let dl_url = get_download_url(&version);
+        set_current_version(Some(&version))?;
+        write_current_channel(channel.as_ref())?;
 
-            let res = reqwest::blocking::get(get_download_url(&version))?;
-            let mut reader =
-                progress::Reader::new(res.content_length(), res, ProgressDrawTarget::stdout());
+        match &channel {
+            Some(channel) => print_success(&format!(
+                "Switched to SDK version {version} (channel {channel})!"
+            )),
+            None => print_success(&format!("Switched to SDK version {version}!")),
+        }
 
-            match get_url_extension(&dl_url) {
-                "gz" | "tgz" => {
-                    let mut arch = Archive::new(GzDecoder::new(BufReader::new(reader)));
-                    arch.unpack(&install_dir)?;
-                }
-                "zip" => {
-                    let mut tmp = tempfile::tempfile()?;
-                    io::copy(&mut reader, &mut tmp)?;
-                    print_status("Unpacking SDK ...");
-                    let mut arch = ZipArchive::new(tmp)?;
-                    arch.extract(&install_dir)?;
-                }
-                _ => {}
+        if let Some(policy) = get_auto_prune_policy()?.filter(|_| install) {
+            for (v, reason) in prune_installed(&policy, false)? {
+                print_note(&format!("Removed SDK {v} ({reason})."));
             }
         }
 
-        link_current_version(Some(&version))?;
-        write_current_version(Some(&version))?;
+        Ok(())
+    }
 
-        print_success(&format!("Switched to SDK version {version}!"));
+    fn lock_mode(&self) -> LockMode {
+        // Automatic pruning removes SDKs, which other processes
+        // may be about to select.
+        match get_auto_prune_policy() {
+            Ok(Some(_)) => LockMode::Exclusive,
+            _ => LockMode::Shared,
+        }
+    }
+}
 
-        Ok(())
+/// Switches to an installed SDK which is not an upstream
+/// release, like SDKs built from source.
+fn select_ident_version(version: &Version) -> anyhow::Result<()> {
+    if !get_available_versions()?.contains(version) {
+        anyhow::bail!(
+            "SDK {version} is not installed. SDKs which are no upstream releases \
+            must be built first using `goup build`."
+        );
     }
+
+    set_current_version(Some(version))?;
+    write_current_channel(None)?;
+
+    print_success(&format!("Switched to SDK version {version}!"));
+
+    Ok(())
 }
 
-fn get_url_extension(url: &str) -> &str {
-    url.split('.').last().unwrap_or_default()
+/// Switches back to the SDK which has been selected before
+/// the current one according to the selection history.
+///
+/// The version policy is enforced like for any other selection.
+/// If upstream versions can not be fetched, the cached ones are
+/// used, so that switching back also works offline.
+fn select_previous_version() -> anyhow::Result<()> {
+    let current = get_current_version()?;
+    let Some(version) = get_previous_version(&read_history()?, current.as_ref()) else {
+        anyhow::bail!("No previously selected SDK found. See `goup history` for past selections.");
+    };
+
+    if !get_available_versions()?.contains(&version) {
+        anyhow::bail!("The previously selected SDK {version} is not installed anymore.");
+    }
+
+    if !version.is_ident() {
+        let upstream_versions = get_upstream_versions().or_else(|_| get_cached_upstream_versions())?;
+        get_policy()?.enforce(&version, &upstream_versions)?;
+    }
+
+    set_current_version(Some(&version))?;
+    write_current_channel(None)?;
+
+    print_success(&format!("Switched back to SDK version {version}!"));
+
+    Ok(())
 }
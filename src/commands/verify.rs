@@ -0,0 +1,90 @@
+use super::{Command, Exit};
+use crate::{env::*, error, resolve::find_installed_version, success, versions::Version, warning};
+use clap::Args;
+use console::style;
+
+/// The number of differences listed per SDK.
+const MAX_LISTED: usize = 10;
+
+const LONG_ABOUT: &str = "\
+Verify installed SDKs against their manifests.
+
+When an SDK is downloaded, a manifest of its files including their sizes \
+and checksums is recorded. This command compares the installed files \
+against the manifest and reports files which are missing, changed or have \
+been added since. Damaged SDKs can be repaired using `goup reinstall`.
+
+SDKs which have been built, linked or installed before manifests were \
+recorded can not be verified.
+
+If no version is passed, the currently selected SDK is verified. The \
+command exits with code 1 if any SDK has been modified.";
+
+/// Verify installed SDKs against their recorded manifests.
+#[derive(Args)]
+#[command(long_about = LONG_ABOUT)]
+pub struct Verify {
+    /// The version or alias of the SDK to verify.
+    version: Option<String>,
+
+    /// Verify all installed SDKs.
+    #[arg(short, long, conflicts_with = "version")]
+    all: bool,
+}
+
+impl Command for Verify {
+    fn run(&self) -> anyhow::Result<()> {
+        let targets = match (&self.version, self.all) {
+            (_, true) => get_installed_versions()?,
+            (Some(v), _) => vec![find_installed_version(v)?],
+            (None, _) => vec![get_current_version()?.ok_or_else(|| {
+                anyhow::anyhow!("No SDK is currently selected. Please pass a version or `--all`.")
+            })?],
+        };
+
+        let mut damaged = 0;
+        for version in &targets {
+            if !verify(version)? {
+                damaged += 1;
+            }
+        }
+
+        if damaged > 0 {
+            return Err(Exit(1).into());
+        }
+
+        Ok(())
+    }
+
+    fn lock_mode(&self) -> LockMode {
+        LockMode::Shared
+    }
+}
+
+/// Verifies the given SDK and prints the result. Returns
+/// `false` if the SDK differs from its manifest.
+fn verify(version: &Version) -> anyhow::Result<bool> {
+    let Some(diffs) = verify_installation(version)? else {
+        warning!("SDK {version} can not be verified, because no manifest has been recorded.");
+        return Ok(true);
+    };
+
+    if diffs.is_empty() {
+        success!("SDK {version} is intact.");
+        return Ok(true);
+    }
+
+    error!("SDK {version} differs from its manifest:");
+    for diff in diffs.iter().take(MAX_LISTED) {
+        println!("  {diff}");
+    }
+    if diffs.len() > MAX_LISTED {
+        println!("  ... and {} more", diffs.len() - MAX_LISTED);
+    }
+    println!(
+        "  {}",
+        style(format!("Use `goup reinstall {version}` to repair it.")).dim()
+    );
+
+    Ok(false)
+}
@@ -0,0 +1,612 @@
+use crate::{profile, prune::PrunePolicy, tui, versions::Channel};
+use anyhow::Result;
+use directories::BaseDirs;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs, io,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+use whattheshell::Shell;
+
+/// Environment variable which can be set to the path of
+/// the configuration file.
+pub const CONFIG_ENV: &str = "GOUP_CONFIG";
+
+/// Environment variable which can be set to the home
+/// directory of goup, overriding the `home` key.
+pub const HOME_ENV: &str = "GOUP_HOME";
+
+pub const CHANNEL: &str = "channel";
+pub const DOWNLOAD_MIRROR: &str = "download_mirror";
+pub const TAGS_ENDPOINT: &str = "tags_endpoint";
+pub const RELEASE_HISTORY_URL: &str = "release_history_url";
+pub const CACHE_TTL: &str = "cache_ttl";
+pub const PRUNE_AUTO: &str = "prune.auto";
+pub const POLICY: &str = "policy";
+pub const COLOR: &str = "color";
+pub const SHELL: &str = "shell";
+pub const HOME: &str = "home";
+pub const XDG: &str = "xdg";
+pub const LOCK_TIMEOUT: &str = "lock_timeout";
+pub const DEDUP: &str = "dedup";
+pub const INSTALL_PROFILE: &str = "install.profile";
+pub const INSTALL_INCLUDE: &str = "install.include";
+pub const INSTALL_EXCLUDE: &str = "install.exclude";
+pub const SYSTEM_STORE: &str = "system_store";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Invalid values which have already been reported.
+static REPORTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Definition of a configuration key.
+pub struct Key {
+    /// Name of the key. Dots separate the table and
+    /// the key name in the configuration file.
+    pub name: &'static str,
+    /// Environment variable overriding the configured value.
+    pub env: &'static str,
+    /// Value used when the key is neither configured nor
+    /// overridden by the environment.
+    pub default: Option<&'static str>,
+    pub description: &'static str,
+    validate: fn(&str) -> Result<()>,
+}
+
+/// All available configuration keys.
+pub const KEYS: &[Key] = &[
+    Key {
+        name: CHANNEL,
+        env: "GOUP_CHANNEL",
+        default: None,
+        description:
+            "Release channel used by `goup use` without arguments when no channel is tracked",
+        validate: validate_channel,
+    },
+    Key {
+        name: DOWNLOAD_MIRROR,
+        env: "GOUP_DOWNLOAD_MIRROR",
+        default: Some("https://go.dev/dl"),
+        description: "Base URL SDK archives are downloaded from",
+        validate: validate_url,
+    },
+    Key {
+        name: TAGS_ENDPOINT,
+        env: "GOUP_TAGS_ENDPOINT",
+        default: Some("https://api.github.com/repos/golang/go/git/matching-refs/tags/go"),
+        description: "GitHub API endpoint listing the tags of the Go repository",
+        validate: validate_url,
+    },
+    Key {
+        name: RELEASE_HISTORY_URL,
+        env: "GOUP_RELEASE_HISTORY_URL",
+        default: Some("https://go.dev/doc/devel/release"),
+        description: "URL or file path of the Go release history",
+        validate: validate_any,
+    },
+    Key {
+        name: CACHE_TTL,
+        env: "GOUP_CACHE_TTL",
+        default: Some("1day"),
+        description: "Time after which cached upstream versions are considered outdated (like `12h`)",
+        validate: validate_duration,
+    },
+    Key {
+        name: PRUNE_AUTO,
+        env: "GOUP_AUTO_PRUNE",
+        default: None,
+        description: "Retention policy applied after installing SDKs (see `goup help prune`)",
+        validate: validate_prune_policy,
+    },
+    Key {
+        name: POLICY,
+        env: "GOUP_POLICY",
+        default: None,
+        description: "Path of a version policy file further restricting the system policy",
+        validate: validate_file,
+    },
+    Key {
+        name: COLOR,
+        env: "GOUP_COLOR",
+        default: Some("auto"),
+        description: "Whether output is colored (`auto`, `always` or `never`)",
+        validate: validate_color,
+    },
+    Key {
+        name: SHELL,
+        env: "GOUP_SHELL",
+        default: None,
+        description: "Shell used instead of the inferred shell (like `bash` or `fish`)",
+        validate: validate_shell,
+    },
+    Key {
+        name: HOME,
+        env: HOME_ENV,
+        default: None,
+        description: "Directory where SDKs and goup's state are stored (like `/opt/goup`)",
+        validate: validate_any,
+    },
+    Key {
+        name: XDG,
+        env: "GOUP_XDG",
+        default: Some("false"),
+        description: "Whether data, cache and state are split into the XDG base directories",
+        validate: validate_bool,
+    },
+    Key {
+        name: LOCK_TIMEOUT,
+        env: "GOUP_LOCK_TIMEOUT",
+        default: Some("1min"),
+        description: "Time to wait for other goup processes to release their locks (like `30s`)",
+        validate: validate_duration,
+    },
+    Key {
+        name: DEDUP,
+        env: "GOUP_DEDUP",
+        default: Some("false"),
+        description: "Whether identical files of SDKs are hard-linked after installing (see `goup help dedup`)",
+        validate: validate_bool,
+    },
+    Key {
+        name: INSTALL_PROFILE,
+        env: "GOUP_INSTALL_PROFILE",
+        default: Some("full"),
+        description: "Files unpacked when installing SDKs (`full`, `minimal` or `custom`)",
+        validate: validate_install_profile,
+    },
+    Key {
+        name: INSTALL_INCLUDE,
+        env: "GOUP_INSTALL_INCLUDE",
+        default: None,
+        description: "Comma separated globs of paths the `custom` install profile unpacks anyway",
+        validate: validate_patterns,
+    },
+    Key {
+        name: INSTALL_EXCLUDE,
+        env: "GOUP_INSTALL_EXCLUDE",
+        default: None,
+        description: "Comma separated globs of paths the `custom` install profile skips (like `go/test,**/testdata`)",
+        validate: validate_patterns,
+    },
+    Key {
+        name: SYSTEM_STORE,
+        env: "GOUP_SYSTEM_STORE",
+        default: None,
+        description: "Directory of the SDK store shared by all users (defaults to `/opt/goup`, empty to disable)",
+        validate: validate_any,
+    },
+];
+
+fn validate_any(_: &str) -> Result<()> {
+    Ok(())
+}
+
+fn validate_file(v: &str) -> Result<()> {
+    match PathBuf::from(v).is_file() {
+        true => Ok(()),
+        false => anyhow::bail!("file {v} does not exist"),
+    }
+}
+
+fn validate_bool(v: &str) -> Result<()> {
+    v.parse::<bool>()
+        .map(|_| ())
+        .map_err(|_| anyhow::anyhow!("must be `true` or `false`"))
+}
+
+fn validate_channel(v: &str) -> Result<()> {
+    v.parse::<Channel>().map(|_| ())
+}
+
+fn validate_url(v: &str) -> Result<()> {
+    if !v.starts_with("http://") && !v.starts_with("https://") {
+        anyhow::bail!("must be a http or https URL");
+    }
+    Ok(())
+}
+
+fn validate_duration(v: &str) -> Result<()> {
+    humantime::parse_duration(v)
+        .map(|_| ())
+        .map_err(|err| anyhow::anyhow!("invalid duration: {err}"))
+}
+
+fn validate_prune_policy(v: &str) -> Result<()> {
+    v.parse::<PrunePolicy>().map(|_| ())
+}
+
+fn validate_install_profile(v: &str) -> Result<()> {
+    match v {
+        profile::FULL | profile::MINIMAL | profile::CUSTOM => Ok(()),
+        _ => anyhow::bail!("must be one of `full`, `minimal` or `custom`"),
+    }
+}
+
+fn validate_patterns(v: &str) -> Result<()> {
+    profile::parse_patterns(v).map(|_| ())
+}
+
+fn validate_color(v: &str) -> Result<()> {
+    match v {
+        "auto" | "always" | "never" => Ok(()),
+        _ => anyhow::bail!("must be one of `auto`, `always` or `never`"),
+    }
+}
+
+fn validate_shell(v: &str) -> Result<()> {
+    v.parse::<Shell>()
+        .map(|_| ())
+        .map_err(|_| anyhow::anyhow!("unsupported shell"))
+}
+
+/// Returns the definition of the given configuration key.
+pub fn get_key(name: &str) -> Result<&'static Key> {
+    KEYS.iter()
+        .find(|k| k.name == name)
+        .ok_or_else(|| anyhow::anyhow!("unknown configuration key `{name}`"))
+}
+
+/// Where the effective value of a configuration key comes from.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Source {
+    Env(&'static str),
+    File,
+    Default,
+    Unset,
+}
+
+/// Configuration of goup read from the configuration file
+/// *(see [`get_config_file`])*, where each value can be
+/// overridden via an environment variable.
+///
+/// # Example
+/// ```toml
+/// channel = "stable"
+/// download_mirror = "https://golang.google.cn/dl"
+/// cache_ttl = "12h"
+///
+/// [prune]
+/// auto = "keep-latest-patch"
+/// ```
+#[derive(Default, Debug)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Reads the configuration file.
+    ///
+    /// Values are validated when they are read *(see [`Config::get`])*,
+    /// so a single invalid value does not break all commands. Use
+    /// [`Config::problems`] to list all invalid values.
+    pub fn load() -> Result<Self> {
+        let path = get_config_file()?;
+
+        let content = match fs::read_to_string(&path) {
+            Ok(v) => v,
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => String::new(),
+            Err(err) => anyhow::bail!("failed reading config file {}: {err}", path.display()),
+        };
+
+        Self::parse(&content)
+            .map_err(|err| anyhow::anyhow!("invalid config file {}: {err}", path.display()))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(content)?;
+
+        let mut values = BTreeMap::new();
+        flatten(&table, "", &mut values)?;
+
+        Ok(Self { values })
+    }
+
+    /// Returns all unknown keys and invalid values in the
+    /// configuration file and the environment overrides.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        for name in self.values.keys() {
+            if get_key(name).is_err() {
+                problems.push(format!("unknown configuration key `{name}`"));
+            }
+        }
+
+        for key in KEYS {
+            let (_, _, errs) = self.lookup(key);
+            problems.extend(errs);
+        }
+
+        problems
+    }
+
+    /// Returns the effective value of the given key and where
+    /// it comes from.
+    ///
+    /// Invalid values are skipped and reported as warning, so the
+    /// next valid source is used instead.
+    pub fn get(&self, name: &str) -> Result<(Option<String>, Source)> {
+        let key = get_key(name)?;
+
+        let (value, source, errs) = self.lookup(key);
+        for err in errs {
+            if REPORTED.lock().is_ok_and(|mut r| r.insert(err.clone())) {
+                tui::eprint_warning(&format!("Ignoring {err}."));
+            }
+        }
+
+        Ok((value, source))
+    }
+
+    /// Returns the first valid value of the given key and where it
+    /// comes from, along with the errors of all skipped values.
+    pub fn lookup(&self, key: &Key) -> (Option<String>, Source, Vec<String>) {
+        let mut errs = vec![];
+
+        if let Some(v) = env_value(key) {
+            match (key.validate)(&v) {
+                Ok(_) => return (Some(v), Source::Env(key.env), errs),
+                Err(err) => errs.push(format!(
+                    "invalid value for `{}` in environment variable {}: {err}",
+                    key.name, key.env
+                )),
+            }
+        }
+
+        if let Some(v) = self.values.get(key.name) {
+            match (key.validate)(v) {
+                Ok(_) => return (Some(v.clone()), Source::File, errs),
+                Err(err) => errs.push(format!("invalid value for `{}`: {err}", key.name)),
+            }
+        }
+
+        match key.default {
+            Some(v) => (Some(v.to_string()), Source::Default, errs),
+            None => (None, Source::Unset, errs),
+        }
+    }
+
+    /// Returns the effective value of the given key.
+    ///
+    /// # Panics
+    /// Panics if the key is not defined in [`KEYS`].
+    pub fn value(&self, name: &str) -> Option<String> {
+        self.get(name).expect("undefined configuration key").0
+    }
+
+    /// Validates and sets the value of the given key in the
+    /// configuration file.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let key = get_key(name)?;
+        (key.validate)(value)
+            .map_err(|err| anyhow::anyhow!("invalid value for `{name}`: {err}"))?;
+        self.values.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Removes the given key from the configuration file. Unknown
+    /// keys can be removed as well.
+    pub fn unset(&mut self, name: &str) -> Result<()> {
+        if self.values.remove(name).is_none() {
+            get_key(name)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the configuration to the configuration file.
+    pub fn save(&self) -> Result<()> {
+        let mut table = toml::Table::new();
+        for (name, value) in &self.values {
+            let mut t = &mut table;
+            let mut parts: Vec<_> = name.split('.').collect();
+            let last = parts.pop().unwrap_or_default();
+            for part in parts {
+                t = t
+                    .entry(part)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                    .as_table_mut()
+                    .ok_or_else(|| anyhow::anyhow!("`{part}` is not a table"))?;
+            }
+            t.insert(last.to_string(), toml::Value::String(value.clone()));
+        }
+
+        let path = get_config_file()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&table)?)?;
+
+        Ok(())
+    }
+
+    /// Returns the configured default release channel.
+    pub fn channel(&self) -> Option<Channel> {
+        self.value(CHANNEL).and_then(|v| v.parse().ok())
+    }
+
+    /// Returns the base URL SDKs are downloaded from.
+    pub fn download_mirror(&self) -> String {
+        self.value(DOWNLOAD_MIRROR)
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Returns the duration after which cached data is outdated.
+    pub fn cache_ttl(&self) -> Duration {
+        self.value(CACHE_TTL)
+            .and_then(|v| humantime::parse_duration(&v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the time to wait for locks held by
+    /// other goup processes.
+    pub fn lock_timeout(&self) -> Duration {
+        self.value(LOCK_TIMEOUT)
+            .and_then(|v| humantime::parse_duration(&v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the configured shell.
+    pub fn shell(&self) -> Option<Shell> {
+        self.value(SHELL).and_then(|v| v.parse().ok())
+    }
+
+    /// Returns the configured home directory of goup.
+    pub fn home(&self) -> Option<PathBuf> {
+        self.value(HOME).map(PathBuf::from)
+    }
+
+    /// Returns whether data, cache and state are split into
+    /// the XDG base directories.
+    pub fn xdg(&self) -> bool {
+        self.value(XDG).is_some_and(|v| v == "true")
+    }
+
+    /// Returns whether identical files of installed SDKs are
+    /// hard-linked after installing an SDK.
+    pub fn dedup(&self) -> bool {
+        self.value(DEDUP).is_some_and(|v| v == "true")
+    }
+
+    /// Returns whether colored output is forced on or off.
+    /// [`None`] is returned if it is detected automatically.
+    pub fn colors_enabled(&self) -> Option<bool> {
+        match self.value(COLOR).as_deref() {
+            Some("always") => Some(true),
+            Some("never") => Some(false),
+            _ => None,
+        }
+    }
+}
+
+fn env_value(key: &Key) -> Option<String> {
+    env::var(key.env).ok().filter(|v| !v.is_empty())
+}
+
+fn flatten(table: &toml::Table, prefix: &str, values: &mut BTreeMap<String, String>) -> Result<()> {
+    for (k, v) in table {
+        let name = format!("{prefix}{k}");
+        match v {
+            toml::Value::Table(t) => flatten(t, &format!("{name}."), values)?,
+            toml::Value::String(s) => {
+                values.insert(name, s.clone());
+            }
+            toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                values.insert(name, v.to_string());
+            }
+            _ => anyhow::bail!("invalid value for `{name}`: unsupported type"),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the path of the configuration file.
+///
+/// # Example
+/// ```
+/// let file = get_config_file().unwrap();
+/// // -> "/home/me/.config/goup/config.toml"
+/// ```
+pub fn get_config_file() -> Result<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV).filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let dir = match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => BaseDirs::new()
+            .ok_or_else(|| anyhow::anyhow!("could not find user directories"))?
+            .config_dir()
+            .to_path_buf(),
+    };
+
+    Ok(dir.join("goup").join("config.toml"))
+}
+
+/// Reads the configuration and initializes the global
+/// configuration *(see [`get_config`])*.
+pub fn init_config() -> Result<()> {
+    let config = Config::load()?;
+    _ = CONFIG.set(config);
+    Ok(())
+}
+
+/// Returns the global configuration. If it has not been
+/// initialized *(see [`init_config`])*, it is loaded, falling
+/// back to the default configuration on errors.
+pub fn get_config() -> &'static Config {
+    CONFIG.get_or_init(|| Config::load().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(
+            r#"
+            channel = "1.22"
+            cache_ttl = "12h"
+
+            [prune]
+            auto = "keep-minors=2"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some("1.22".to_string()),
+            config.values.get(CHANNEL).cloned()
+        );
+        assert_eq!(
+            Some("keep-minors=2".to_string()),
+            config.values.get(PRUNE_AUTO).cloned()
+        );
+        assert_eq!(Duration::from_secs(12 * 60 * 60), config.cache_ttl());
+
+        assert!(config.problems().is_empty());
+        assert!(Config::parse("channel = ").is_err());
+    }
+
+    #[test]
+    fn invalid_values() {
+        let mut config = Config::parse(
+            r#"
+            lock_timeout = "soon"
+            foo = "bar"
+
+            [prune]
+            auto = "foo"
+            "#,
+        )
+        .unwrap();
+
+        let problems = config.problems();
+        assert_eq!(3, problems.len());
+        assert!(problems[0].contains("`foo`"));
+        assert!(problems[1].contains("`prune.auto`"));
+        assert!(problems[2].contains("`lock_timeout`"));
+
+        // Invalid values are skipped when read.
+        let (value, source) = config.get(LOCK_TIMEOUT).unwrap();
+        assert_eq!(Some("1min".to_string()), value);
+        assert_eq!(Source::Default, source);
+
+        config.unset("foo").unwrap();
+        config.unset(PRUNE_AUTO).unwrap();
+        assert_eq!(1, config.problems().len());
+    }
+
+    #[test]
+    fn get_default() {
+        let config = Config::default();
+        let (value, source) = config.get(COLOR).unwrap();
+        assert_eq!(Some("auto".to_string()), value);
+        assert_eq!(Source::Default, source);
+        assert!(config.get("foo").is_err());
+    }
+}
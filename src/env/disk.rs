@@ -0,0 +1,396 @@
+use super::*;
+use crate::versions::Version;
+use anyhow::Result;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Disk usage of an installed SDK.
+pub struct DiskUsage {
+    pub version: Version,
+
+    /// The size of all files of the SDK in bytes.
+    pub size: u64,
+
+    /// The size of the files which are not shared with other
+    /// SDKs via hard links, which is freed when dropping it.
+    pub exclusive: u64,
+}
+
+/// The result of a deduplication pass.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct DedupResult {
+    /// The number of files replaced by hard links.
+    pub linked: usize,
+
+    /// The number of bytes freed.
+    pub saved: u64,
+}
+
+/// A regular file found in an installation directory.
+struct FileInfo {
+    path: PathBuf,
+    meta: fs::Metadata,
+}
+
+impl FileInfo {
+    /// Returns an identifier which is equal for all hard links
+    /// to the same file. If hard links can not be detected,
+    /// the path is used instead.
+    fn id(&self) -> FileKey {
+        match get_file_id(&self.meta) {
+            Some(id) => FileKey::Id(id),
+            None => FileKey::Path(self.path.clone()),
+        }
+    }
+}
+
+/// A checksum recorded in the manifest of an SDK, by the
+/// path of the file.
+struct KnownHash {
+    entry: ManifestEntry,
+
+    /// When the manifest has been written.
+    recorded_at: SystemTime,
+}
+
+impl KnownHash {
+    /// Returns the recorded checksum if the file has not been
+    /// modified since the manifest has been written.
+    fn get(&self, file: &FileInfo) -> Option<&str> {
+        let unchanged = self.entry.size == file.meta.len()
+            && file.meta.modified().is_ok_and(|t| t <= self.recorded_at);
+        unchanged.then_some(self.entry.sha256.as_str())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum FileKey {
+    Id((u64, u64)),
+    Path(PathBuf),
+}
+
+/// Recursively collects all regular files in the given directory.
+/// Symlinks are not followed, so SDKs linked from another
+/// location are never touched.
+fn collect_files(dir: &Path, files: &mut Vec<FileInfo>) -> Result<()> {
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(FileInfo {
+                path: entry.path(),
+                meta: entry.metadata()?,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the disk usage of all installed SDKs and the
+/// total size of the installations directory, where files
+/// shared via hard links are counted once.
+pub fn get_disk_usage() -> Result<(Vec<DiskUsage>, u64)> {
+    let mut sdks = vec![];
+    let mut owners: HashMap<FileKey, (u64, usize)> = HashMap::new();
+
+    for version in get_installed_versions()? {
+        let mut files = vec![];
+        collect_files(&get_version_installation_dir(&version)?, &mut files)?;
+
+        let mut ids = BTreeMap::new();
+        for file in files {
+            ids.insert(file.id(), file.meta.len());
+        }
+        for (id, size) in &ids {
+            let owner = owners.entry(id.clone()).or_insert((*size, 0));
+            owner.1 += 1;
+        }
+
+        sdks.push((version, ids));
+    }
+
+    let usage = sdks
+        .into_iter()
+        .map(|(version, ids)| DiskUsage {
+            version,
+            size: ids.values().sum(),
+            exclusive: ids
+                .iter()
+                .filter(|(id, _)| owners[id].1 == 1)
+                .map(|(_, size)| size)
+                .sum(),
+        })
+        .collect();
+
+    let total = owners.values().map(|(size, _)| size).sum();
+
+    Ok((usage, total))
+}
+
+/// Replaces identical files of all installed SDKs by hard
+/// links to a single copy *(see [`dedup_dirs`])*.
+///
+/// SDKs which are still being installed by another goup
+/// process are skipped, because their files may still be
+/// written.
+pub fn dedup_installations() -> Result<DedupResult> {
+    let mut dirs = vec![];
+    let mut known = HashMap::new();
+    for version in get_installed_versions()? {
+        if is_installed(&version)? {
+            let dir = get_version_installation_dir(&version)?;
+            add_known_hashes(&version, &dir, &mut known)?;
+            dirs.push(dir);
+        }
+    }
+    dedup_dirs(&dirs, &known)
+}
+
+/// Adds the checksums recorded in the manifest of the given
+/// SDK *(see [`read_manifest`])*, so unchanged files do not
+/// need to be read again.
+fn add_known_hashes(
+    version: &Version,
+    dir: &Path,
+    known: &mut HashMap<PathBuf, KnownHash>,
+) -> Result<()> {
+    let Some(manifest) = read_manifest(version)? else {
+        return Ok(());
+    };
+    let recorded_at = fs::metadata(get_manifest_file(version)?)?.modified()?;
+
+    for (path, entry) in manifest.files {
+        known.insert(dir.join(path), KnownHash { entry, recorded_at });
+    }
+    Ok(())
+}
+
+/// Replaces files in the given directories which are identical
+/// in size, permissions and contents by hard links to a single
+/// copy.
+///
+/// Checksums are taken from `known` for files which have not
+/// been modified since their manifest has been recorded and
+/// are computed otherwise. Because recorded checksums can not
+/// prove that a file is unchanged, the contents of each file
+/// are compared with the original before it is replaced.
+///
+/// Each file is replaced atomically by renaming a new hard link
+/// over it, so the files are readable at any time. Because
+/// removing a hard link only removes the link itself, deleting
+/// one of the directories afterwards leaves the files of all
+/// other directories intact.
+fn dedup_dirs(dirs: &[PathBuf], known: &HashMap<PathBuf, KnownHash>) -> Result<DedupResult> {
+    if cfg!(windows) {
+        anyhow::bail!("Deduplication is not supported on Windows.");
+    }
+
+    let mut files = vec![];
+    for dir in dirs {
+        collect_files(dir, &mut files)?;
+    }
+
+    let mut by_size: HashMap<u64, Vec<FileInfo>> = HashMap::new();
+    for file in files.into_iter().filter(|f| f.meta.len() > 0) {
+        by_size.entry(file.meta.len()).or_default().push(file);
+    }
+
+    let mut res = DedupResult::default();
+    for (size, group) in by_size {
+        // Files with a unique size can not have duplicates.
+        if group.len() < 2 {
+            continue;
+        }
+
+        let mut by_content: HashMap<(String, u64), Vec<FileInfo>> = HashMap::new();
+        let mut hashes: HashMap<FileKey, String> = HashMap::new();
+
+        for file in group {
+            let hash = match hashes.get(&file.id()) {
+                Some(v) => v.clone(),
+                None => {
+                    let hash = match known.get(&file.path).and_then(|k| k.get(&file)) {
+                        Some(v) => v.to_string(),
+                        None => hash_file(&file.path)?.sha256,
+                    };
+                    hashes.insert(file.id(), hash.clone());
+                    hash
+                }
+            };
+            let dev = match file.id() {
+                FileKey::Id((dev, _)) => dev,
+                FileKey::Path(_) => 0,
+            };
+            by_content.entry((hash, dev)).or_default().push(file);
+        }
+
+        for identical in by_content.into_values() {
+            let mut originals: Vec<&FileInfo> = vec![];
+            let mut replaced = vec![];
+
+            for file in &identical {
+                let original = originals
+                    .iter()
+                    .find(|o| o.meta.permissions() == file.meta.permissions());
+                match original {
+                    None => originals.push(file),
+                    Some(o) if o.id() == file.id() => {}
+                    Some(o) if !same_contents(&o.path, &file.path)? => {}
+                    Some(o) => {
+                        link_file(&o.path, &file.path)?;
+                        res.linked += 1;
+                        if !replaced.contains(&file.id()) {
+                            replaced.push(file.id());
+                        }
+                    }
+                }
+            }
+
+            // All links to a replaced file share its permissions and
+            // are part of this group, so each replaced file is freed.
+            res.saved += replaced.len() as u64 * size;
+        }
+    }
+
+    Ok(res)
+}
+
+/// Returns whether the files `a` and `b` have the same contents.
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let mut a = io::BufReader::new(fs::File::open(a)?);
+    let mut b = io::BufReader::new(fs::File::open(b)?);
+
+    loop {
+        let (chunk_a, chunk_b) = (a.fill_buf()?, b.fill_buf()?);
+        if chunk_a.is_empty() || chunk_b.is_empty() {
+            return Ok(chunk_a.is_empty() && chunk_b.is_empty());
+        }
+
+        let len = chunk_a.len().min(chunk_b.len());
+        if chunk_a[..len] != chunk_b[..len] {
+            return Ok(false);
+        }
+        a.consume(len);
+        b.consume(len);
+    }
+}
+
+/// Atomically replaces the file at `path` by a hard link
+/// to the file `original`.
+fn link_file(original: &Path, path: &Path) -> Result<()> {
+    let tmp = get_temp_path(path);
+    fs::hard_link(original, &tmp)?;
+    if let Err(err) = fs::rename(&tmp, path) {
+        _ = fs::remove_file(&tmp);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_dedup_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = tmp.path().join("1.22.0");
+        let b = tmp.path().join("1.22.1");
+        write(&a, "go/src/fmt.go", "package fmt");
+        write(&a, "go/VERSION", "go1.22.0");
+        write(&b, "go/src/fmt.go", "package fmt");
+        write(&b, "go/VERSION", "go1.22.1");
+
+        let res = dedup_dirs(&[a.clone(), b.clone()], &HashMap::new()).unwrap();
+        assert_eq!(
+            DedupResult {
+                linked: 1,
+                saved: 11
+            },
+            res
+        );
+
+        let id = |p: PathBuf| get_file_id(&fs::metadata(p).unwrap());
+        assert_eq!(id(a.join("go/src/fmt.go")), id(b.join("go/src/fmt.go")));
+        assert_ne!(id(a.join("go/VERSION")), id(b.join("go/VERSION")));
+
+        // Already linked files are skipped.
+        assert_eq!(
+            DedupResult::default(),
+            dedup_dirs(&[a.clone(), b.clone()], &HashMap::new()).unwrap()
+        );
+
+        // Dropping one SDK leaves the shared files of the other intact.
+        fs::remove_dir_all(&a).unwrap();
+        assert_eq!(
+            "package fmt",
+            fs::read_to_string(b.join("go/src/fmt.go")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dedup_dirs_outdated_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = tmp.path().join("1.22.0");
+        let b = tmp.path().join("1.22.1");
+        write(&a, "go/VERSION", "go1.22.0");
+        write(&b, "go/VERSION", "go1.22.1");
+
+        // Both files have been modified since their checksums were
+        // recorded, so the checksums must not be trusted.
+        let known = |dir: &Path| {
+            let entry = ManifestEntry {
+                size: 8,
+                sha256: "same".into(),
+            };
+            let recorded_at = SystemTime::UNIX_EPOCH;
+            (dir.join("go/VERSION"), KnownHash { entry, recorded_at })
+        };
+        let known = HashMap::from([known(&a), known(&b)]);
+
+        let res = dedup_dirs(&[a.clone(), b.clone()], &known).unwrap();
+        assert_eq!(DedupResult::default(), res);
+    }
+
+    #[test]
+    fn test_dedup_dirs_wrong_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = tmp.path().join("1.22.0");
+        let b = tmp.path().join("1.22.1");
+        write(&a, "go/VERSION", "go1.22.0");
+        write(&b, "go/VERSION", "go1.22.1");
+
+        // The checksums look up to date, but do not match the
+        // contents, so the files must not be linked.
+        let known = |dir: &Path| {
+            let entry = ManifestEntry {
+                size: 8,
+                sha256: "same".into(),
+            };
+            let recorded_at = SystemTime::now() + std::time::Duration::from_secs(60);
+            (dir.join("go/VERSION"), KnownHash { entry, recorded_at })
+        };
+        let known = HashMap::from([known(&a), known(&b)]);
+
+        let res = dedup_dirs(&[a.clone(), b.clone()], &known).unwrap();
+        assert_eq!(DedupResult::default(), res);
+        assert_eq!(
+            "go1.22.1",
+            fs::read_to_string(b.join("go/VERSION")).unwrap()
+        );
+    }
+}
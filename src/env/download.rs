@@ -1,49 +1,310 @@
-use crate::versions::Version;
+use super::{
+    dedup_installations, ensure_dir, get_temp_path, get_version_installation_dir, is_installed,
+    lock_install, register_sdk, smoke_test_goroot, write_manifest, Manifest, Origin, Sdk,
+};
+use crate::{
+    config::get_config, profile::InstallProfile, progress, tui::print_status, versions::Version,
+    warning,
+};
+use anyhow::Result;
+use flate2::bufread::GzDecoder;
+use indicatif::ProgressDrawTarget;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, BufReader, Read},
+    path::Path,
+};
+use tar::Archive;
+use zip::read::ZipArchive;
 
 /// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+///
+/// SDKs are downloaded from the configured download mirror,
+/// which defaults to `https://go.dev/dl`.
 pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.linux-amd64.tar.gz")
+    format!(
+        "{}/go{v}.{GOOS}-{GOARCH}.{ARCHIVE_EXTENSION}",
+        get_config().download_mirror()
+    )
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "linux", target_arch = "x86"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.linux-386.tar.gz")
+/// The file extension of SDK archives for the current
+/// operating system.
+#[cfg(not(target_os = "windows"))]
+const ARCHIVE_EXTENSION: &str = "tar.gz";
+
+/// The file extension of SDK archives for the current
+/// operating system.
+#[cfg(target_os = "windows")]
+const ARCHIVE_EXTENSION: &str = "zip";
+
+/// The Go identifier of the current operating system.
+#[cfg(target_os = "linux")]
+pub const GOOS: &str = "linux";
+
+/// The Go identifier of the current operating system.
+#[cfg(target_os = "macos")]
+pub const GOOS: &str = "darwin";
+
+/// The Go identifier of the current operating system.
+#[cfg(target_os = "windows")]
+pub const GOOS: &str = "windows";
+
+/// The Go identifier of the current CPU architecture.
+#[cfg(target_arch = "x86_64")]
+pub const GOARCH: &str = "amd64";
+
+/// The Go identifier of the current CPU architecture.
+#[cfg(target_arch = "x86")]
+pub const GOARCH: &str = "386";
+
+/// The Go identifier of the current CPU architecture.
+#[cfg(target_arch = "aarch64")]
+pub const GOARCH: &str = "arm64";
+
+/// Downloads the SDK of the given version and unpacks it into
+/// its installation directory.
+///
+/// The source URL and the checksum of the downloaded archive
+/// are recorded in the state file and a manifest of the unpacked
+/// files is written *(see [`super::verify_installation`])*.
+///
+/// While installing, the installation of the version is locked.
+/// If another goup process is installing the same version, its
+/// installation is awaited and reused.
+///
+/// Only the files included by the given [`InstallProfile`] are
+/// unpacked.
+///
+/// If the installation fails, the partially unpacked
+/// installation directory is removed.
+pub fn install_version(version: &Version, profile: &InstallProfile) -> Result<()> {
+    let _lock = lock_install(version)?;
+    if is_installed(version)? {
+        return Ok(());
+    }
+
+    let install_dir = get_version_installation_dir(version)?;
+    if install_dir.exists() {
+        // Left over from an interrupted installation.
+        fs::remove_dir_all(&install_dir)?;
+    }
+
+    let (sdk, manifest) = fetch_sdk(version, &install_dir, profile)?;
+    write_manifest(version, &manifest)?;
+    register_sdk(version, sdk)?;
+
+    dedup_if_enabled();
+    Ok(())
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.linux-arm64.tar.gz")
+/// Downloads the SDK of the given version again and replaces
+/// its installation directory with the fresh copy.
+///
+/// The SDK is unpacked next to the installation directory and
+/// swapped in only after it has been downloaded completely, so
+/// the existing installation stays usable if the download fails.
+/// The selection and the pin status of the SDK are kept.
+///
+/// Note that the swap is not atomic: directories can not be
+/// renamed over each other, so the old installation is moved
+/// aside first and the installation directory does not exist
+/// for the short moment between both renames.
+pub fn reinstall_version(version: &Version, profile: &InstallProfile) -> Result<()> {
+    let _lock = lock_install(version)?;
+
+    let install_dir = get_version_installation_dir(version)?;
+    let new_dir = get_temp_path(&install_dir);
+    if new_dir.exists() {
+        fs::remove_dir_all(&new_dir)?;
+    }
+
+    let (sdk, manifest) = fetch_sdk(version, &new_dir, profile)?;
+
+    let old_dir = new_dir.with_extension("old");
+    if install_dir.exists() {
+        fs::rename(&install_dir, &old_dir)?;
+    }
+    if let Err(err) = fs::rename(&new_dir, &install_dir) {
+        _ = fs::rename(&old_dir, &install_dir);
+        _ = fs::remove_dir_all(&new_dir);
+        return Err(err.into());
+    }
+    _ = fs::remove_dir_all(&old_dir);
+
+    write_manifest(version, &manifest)?;
+    register_sdk(version, sdk)?;
+
+    dedup_if_enabled();
+    Ok(())
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.darwin-amd64.tar.gz")
+/// Hard-links identical files of the installed SDKs if enabled
+/// via the `dedup` configuration key. Failures are only reported,
+/// because the SDK has been installed successfully anyway.
+fn dedup_if_enabled() {
+    if !get_config().dedup() {
+        return;
+    }
+
+    print_status("Deduplicating SDKs ...");
+    if let Err(err) = dedup_installations() {
+        warning!("Deduplicating SDKs failed: {err}");
+    }
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "macos", target_arch = "x86"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.darwin-386.tar.gz")
+/// Downloads the SDK of the given version and unpacks the files
+/// included by the given profile into the given directory.
+/// Returns the metadata to record for the SDK and a manifest of
+/// the unpacked files.
+///
+/// Before the SDK is accepted, its go binary is run to check
+/// that it works and reports the expected version and platform
+/// *(see [`smoke_test_goroot`])*. If unpacking or this check
+/// fails, the given directory is removed.
+fn fetch_sdk(version: &Version, dir: &Path, profile: &InstallProfile) -> Result<(Sdk, Manifest)> {
+    ensure_dir(dir)?;
+
+    let dl_url = get_download_url(version);
+    let res = download_and_unpack(dir, &dl_url, profile).and_then(|sha256| {
+        smoke_test_goroot(dir.join("go"), version)
+            .map_err(|err| anyhow::anyhow!("the downloaded SDK is not usable: {err}"))?;
+        let manifest = Manifest::create(dir)?;
+        Ok((sha256, manifest))
+    });
+    let (sha256, manifest) = match res {
+        Ok(v) => v,
+        Err(err) => {
+            _ = fs::remove_dir_all(dir);
+            return Err(err);
+        }
+    };
+
+    let sdk = Sdk {
+        source_url: Some(dl_url),
+        sha256: Some(sha256),
+        profile: Some(profile.name.clone()).filter(|_| !profile.is_full()),
+        included: profile.include_patterns(),
+        excluded: profile.exclude_patterns(),
+        ..Sdk::new(version, Origin::Downloaded)
+    };
+
+    Ok((sdk, manifest))
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.darwin-arm64.tar.gz")
+/// Downloads the archive from `dl_url`, unpacks the entries
+/// included by the given profile into the given directory and
+/// returns the hex encoded SHA-256 checksum of the archive.
+///
+/// Entries are filtered while streaming the archive, so
+/// excluded files are never written to disk.
+fn download_and_unpack(dir: &Path, dl_url: &str, profile: &InstallProfile) -> Result<String> {
+    let res = reqwest::blocking::get(dl_url)?.error_for_status()?;
+    let mut reader = HashReader::new(progress::Reader::new(
+        res.content_length(),
+        res,
+        ProgressDrawTarget::stdout(),
+    ));
+
+    match get_url_extension(dl_url) {
+        "gz" | "tgz" => {
+            let mut arch = Archive::new(GzDecoder::new(BufReader::new(&mut reader)));
+            if profile.is_full() {
+                arch.unpack(dir)?;
+            } else {
+                for entry in arch.entries()? {
+                    let mut entry = entry?;
+                    if profile.includes(&entry.path()?) {
+                        entry.unpack_in(dir)?;
+                    }
+                }
+            }
+        }
+        "zip" => {
+            let mut tmp = tempfile::tempfile()?;
+            io::copy(&mut reader, &mut tmp)?;
+            print_status("Unpacking SDK ...");
+            let mut arch = ZipArchive::new(tmp)?;
+            if profile.is_full() {
+                arch.extract(dir)?;
+            } else {
+                extract_zip_filtered(&mut arch, dir, profile)?;
+            }
+        }
+        _ => {}
+    }
+
+    // Unpacking may stop before the end of the archive,
+    // so the rest is consumed to complete the checksum.
+    io::copy(&mut reader, &mut io::sink())?;
+
+    Ok(reader.hex_digest())
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "windows", target_arch = "x86_64"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.windows-amd64.zip")
+/// Extracts the entries of the given zip archive included
+/// by the given profile into the given directory.
+fn extract_zip_filtered<R: Read + io::Seek>(
+    arch: &mut ZipArchive<R>,
+    dir: &Path,
+    profile: &InstallProfile,
+) -> Result<()> {
+    for i in 0..arch.len() {
+        let mut file = arch.by_index(i)?;
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+        if !profile.includes(&path) {
+            continue;
+        }
+
+        let target = dir.join(path);
+        if file.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut file, &mut fs::File::create(&target)?)?;
+    }
+
+    Ok(())
 }
 
-/// Returns the GO SDK download URL for the given version.
-#[cfg(all(target_os = "windows", target_arch = "x86"))]
-pub fn get_download_url(v: &Version) -> String {
-    format!("https://go.dev/dl/go{v}.windows-386.zip")
+/// A reader which calculates the SHA-256 checksum of
+/// all data read through it.
+struct HashReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Returns the hex encoded checksum of the data read so far.
+    fn hex_digest(self) -> String {
+        self.hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+fn get_url_extension(url: &str) -> &str {
+    url.split('.').next_back().unwrap_or_default()
 }
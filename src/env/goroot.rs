@@ -0,0 +1,271 @@
+use super::{download::*, *};
+use crate::{cmd::exec_with_env, versions::Version, warning};
+use anyhow::Result;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(windows))]
+/// File name of the go binary in the `bin` directory of an SDK.
+pub const GO_BINARY: &str = "go";
+#[cfg(windows)]
+/// File name of the go binary in the `bin` directory of an SDK.
+pub const GO_BINARY: &str = "go.exe";
+
+/// Defines how an SDK located outside of goup's work
+/// directory is transferred into the installations
+/// directory.
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum AdoptMode {
+    /// Move the SDK directory.
+    Move,
+    /// Copy the SDK directory.
+    Copy,
+    /// Create a symlink to the SDK directory.
+    Link,
+}
+
+/// Reads the [`Version`] of the Go SDK in the given
+/// `GOROOT` directory from its `VERSION` file.
+///
+/// # Example
+/// ```
+/// let version = get_goroot_version("/home/me/sdk/go1.22.3").unwrap();
+/// // -> 1.22.3
+/// ```
+pub fn get_goroot_version<P: AsRef<Path>>(goroot: P) -> Result<Version> {
+    let content = fs::read_to_string(goroot.as_ref().join("VERSION"))
+        .map_err(|err| anyhow::anyhow!("failed reading VERSION file: {err}"))?;
+
+    let line = content.lines().next().unwrap_or_default().trim();
+    let Some(version) = line.strip_prefix("go") else {
+        anyhow::bail!("VERSION file contains no release version: {line}");
+    };
+
+    version.parse()
+}
+
+/// Checks if the given directory is a valid `GOROOT`
+/// of the given [`Version`] for the current platform.
+///
+/// # Errors
+/// If the directory is not a valid `GOROOT`, an error is
+/// returned containing the reason.
+pub fn check_goroot<P: AsRef<Path>>(goroot: P, version: &Version) -> Result<()> {
+    let goroot = goroot.as_ref();
+
+    if !goroot.join("bin").join(GO_BINARY).is_file() {
+        anyhow::bail!("no go binary found in bin directory");
+    }
+
+    let found = get_goroot_version(goroot)?;
+    if &found != version {
+        anyhow::bail!("expected version {version}, but SDK is version {found}");
+    }
+
+    let tool_dir = goroot
+        .join("pkg")
+        .join("tool")
+        .join(format!("{GOOS}_{GOARCH}"));
+    if !tool_dir.is_dir() {
+        anyhow::bail!("SDK has not been built for {GOOS}/{GOARCH}");
+    }
+
+    Ok(())
+}
+
+/// Runs the go binary of the SDK in the given `GOROOT`
+/// directory and checks that it reports the given [`Version`],
+/// the current platform and the directory itself as `GOROOT`.
+///
+/// This detects SDKs which can not be executed, like archives
+/// built for another platform served by a mirror.
+///
+/// # Errors
+/// If the go binary can not be run or reports unexpected
+/// values, an error is returned containing the reason.
+pub fn smoke_test_goroot<P: AsRef<Path>>(goroot: P, version: &Version) -> Result<()> {
+    let goroot = goroot.as_ref();
+    let go = goroot.join("bin").join(GO_BINARY);
+    let go = go.to_string_lossy();
+
+    // Neither a GOROOT set by the user nor a toolchain switch may
+    // cause another SDK to answer in place of the tested one.
+    let envs = [("GOROOT", ""), ("GOTOOLCHAIN", "local")];
+
+    let output = exec_with_env(&[&go, "version"], &envs)
+        .map_err(|err| anyhow::anyhow!("failed running `go version`: {err}"))?;
+    check_version_output(&output, version)?;
+
+    let reported = exec_with_env(&[&go, "env", "GOROOT"], &envs)
+        .map_err(|err| anyhow::anyhow!("failed running `go env GOROOT`: {err}"))?;
+    let reported = Path::new(reported.trim());
+    if reported.canonicalize().ok() != goroot.canonicalize().ok() {
+        anyhow::bail!(
+            "go reports GOROOT {}, but the SDK is located in {}",
+            reported.display(),
+            goroot.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the given output of `go version` reports the
+/// given [`Version`] and the current platform.
+///
+/// # Example
+/// ```
+/// check_version_output("go version go1.22.3 linux/amd64", &"1.22.3".parse().unwrap()).unwrap();
+/// ```
+fn check_version_output(output: &str, version: &Version) -> Result<()> {
+    let fields: Vec<_> = output.split_whitespace().collect();
+    let [_, _, found, platform, ..] = fields[..] else {
+        anyhow::bail!("unexpected output of `go version`: {}", output.trim());
+    };
+
+    if found != format!("go{version}") {
+        anyhow::bail!("expected version go{version}, but go reports {found}");
+    }
+
+    if platform != format!("{GOOS}/{GOARCH}") {
+        anyhow::bail!("expected platform {GOOS}/{GOARCH}, but go reports {platform}");
+    }
+
+    Ok(())
+}
+
+/// Transfers the SDK located in the given `GOROOT` directory
+/// into the installations directory as the given [`Version`]
+/// using the given [`AdoptMode`] and records it in the
+/// state file.
+pub fn adopt_sdk<P: AsRef<Path>>(goroot: P, version: &Version, mode: AdoptMode) -> Result<()> {
+    let goroot = goroot.as_ref();
+    let install_dir = get_version_installation_dir(version)?;
+    let target = install_dir.join("go");
+
+    let _lock = lock_install(version)?;
+
+    ensure_dir(&install_dir)?;
+
+    let res = match mode {
+        AdoptMode::Link => goroot
+            .canonicalize()
+            .map_err(Into::into)
+            .and_then(|original| link_dir(original, &target)),
+        AdoptMode::Copy => copy_dir_all(goroot, &target),
+        AdoptMode::Move => move_dir(goroot, &target),
+    };
+
+    if res.is_err() {
+        fs::remove_dir_all(&install_dir).ok();
+        return res;
+    }
+
+    let origin = match mode {
+        AdoptMode::Link => Origin::Linked,
+        AdoptMode::Copy | AdoptMode::Move => Origin::Imported,
+    };
+    register_sdk(
+        version,
+        Sdk {
+            source_url: Some(goroot.to_string_lossy().to_string()),
+            ..Sdk::new(version, origin)
+        },
+    )
+}
+
+/// Moves the directory `src` to `dst`.
+///
+/// Because renaming fails when both locations are on
+/// different file systems, the directory is copied and
+/// removed afterwards in that case. If copying fails, the
+/// partial copy is removed again. Once the copy is complete,
+/// failing to remove `src` only results in a warning, so the
+/// complete copy is never lost.
+pub fn move_dir<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> Result<()> {
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+
+    let existed = dst.exists();
+    if let Err(err) = copy_dir_all(src, dst) {
+        if !existed {
+            _ = fs::remove_dir_all(dst);
+        }
+        return Err(err);
+    }
+
+    if let Err(err) = fs::remove_dir_all(src) {
+        warning!(
+            "{} has been copied to {}, but could not be removed: {err}",
+            src.display(),
+            dst.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Recursively copies the contents of the directory `src`
+/// into the directory `dst`, which is created if it does
+/// not exist. Symlinks are copied as symlinks, so SDKs
+/// linked from another location are not copied.
+pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> Result<()> {
+    let dst: PathBuf = dst.as_ref().into();
+    ensure_dir(&dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            copy_link(entry.path(), target)?;
+        } else if file_type.is_dir() {
+            copy_dir_all(entry.path(), target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_version_output() {
+        let v = "1.22.3".parse().unwrap();
+        let platform = format!("{GOOS}/{GOARCH}");
+
+        assert!(check_version_output(&format!("go version go1.22.3 {platform}\n"), &v).is_ok());
+        assert!(check_version_output(&format!("go version go1.22.2 {platform}"), &v).is_err());
+        assert!(check_version_output("go version go1.22.3 plan9/mips", &v).is_err());
+        assert!(check_version_output("", &v).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dir_all_keeps_symlinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sdk = tmp.path().join("sdk");
+        fs::create_dir_all(sdk.join("go/bin")).unwrap();
+        fs::write(sdk.join("go/bin/go"), "").unwrap();
+
+        let src = tmp.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        link_dir(&sdk, src.join("1.22.0")).unwrap();
+
+        let dst = tmp.path().join("dst");
+        copy_dir_all(&src, &dst).unwrap();
+
+        let link = dst.join("1.22.0");
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(sdk, fs::read_link(link).unwrap());
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+use crate::versions::Version;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// The file where selection changes are appended to.
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// A change of the currently selected SDK.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct HistoryEntry {
+    /// Unix timestamp of the change.
+    pub time: u64,
+
+    /// The previously selected version.
+    pub from: Option<String>,
+
+    /// The newly selected version.
+    pub to: Option<String>,
+
+    /// The goup command which changed the selection
+    /// *(like `use 1.22`)*.
+    pub command: String,
+}
+
+impl HistoryEntry {
+    /// Creates an entry for a selection change caused
+    /// by the currently running goup command.
+    pub fn new(from: Option<String>, to: Option<String>) -> Self {
+        let command: Vec<_> = std::env::args().skip(1).collect();
+        Self {
+            time: now(),
+            from,
+            to,
+            command: command.join(" "),
+        }
+    }
+}
+
+/// Returns the path of the history file.
+///
+/// # Example
+/// ```
+/// let file = get_history_file().unwrap();
+/// // -> "/home/me/.local/goup/history.jsonl"
+/// ```
+pub fn get_history_file() -> Result<PathBuf> {
+    get_state_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Appends the given entry to the history file.
+pub fn append_history(entry: &HistoryEntry) -> Result<()> {
+    ensure_dir(get_state_dir()?)?;
+
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    File::options()
+        .create(true)
+        .append(true)
+        .open(get_history_file()?)?
+        .write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads all entries from the history file, oldest first.
+///
+/// Lines which can not be parsed are skipped.
+pub fn read_history() -> Result<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(get_history_file()?) {
+        Ok(v) => v,
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(vec![]),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Returns the version which has been selected before the
+/// given `current` version according to the given history.
+///
+/// This is the version the latest selection of `current`
+/// has been switched from.
+pub fn get_previous_version(
+    history: &[HistoryEntry],
+    current: Option<&Version>,
+) -> Option<Version> {
+    let current = current.map(|v| v.to_string());
+    history
+        .iter()
+        .rev()
+        .find(|e| e.to == current)
+        .and_then(|e| e.from.as_ref())
+        .and_then(|v| v.parse().ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(from: Option<&str>, to: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            time: 0,
+            from: from.map(Into::into),
+            to: to.map(Into::into),
+            command: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_get_previous_version() {
+        let history = [
+            entry(None, Some("1.21.0")),
+            entry(Some("1.21.0"), Some("1.22.3")),
+            entry(Some("1.22.3"), Some("1.20.1")),
+            entry(Some("1.20.1"), Some("1.22.3")),
+        ];
+
+        let v = |s: &str| s.parse::<Version>().unwrap();
+
+        assert_eq!(
+            Some(v("1.20.1")),
+            get_previous_version(&history, Some(&v("1.22.3")))
+        );
+        assert_eq!(
+            Some(v("1.22.3")),
+            get_previous_version(&history, Some(&v("1.20.1")))
+        );
+        assert_eq!(None, get_previous_version(&history, Some(&v("1.21.0"))));
+        assert_eq!(None, get_previous_version(&history, Some(&v("1.19.0"))));
+        assert_eq!(None, get_previous_version(&[], None));
+    }
+}
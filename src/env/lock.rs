@@ -0,0 +1,224 @@
+use super::*;
+use crate::{config::get_config, config::LOCK_TIMEOUT, tui, versions::Version};
+use anyhow::Result;
+use fs4::FileExt;
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The file locked while a goup command accesses the
+/// working directory.
+const WORK_DIR_LOCK_FILE: &str = "goup.lock";
+
+/// The file locked while the state file is modified.
+const STATE_LOCK_FILE: &str = "state.lock";
+
+/// The file locked while the selected SDK is changed.
+const SELECTION_LOCK_FILE: &str = "selection.lock";
+
+/// Interval in which a contended lock is retried.
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Defines how a command locks goup's working directory
+/// against other goup processes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LockMode {
+    /// The working directory is not locked.
+    None,
+    /// The working directory is locked shared, so that other
+    /// commands with shared locks can run concurrently.
+    Shared,
+    /// The working directory is locked exclusively for
+    /// commands which remove or move SDKs.
+    Exclusive,
+}
+
+/// An advisory file lock which is released when dropped.
+pub struct Lock {
+    file: File,
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Locks goup's working directory using the given [`LockMode`].
+///
+/// Returns [`None`] if [`LockMode::None`] is passed.
+pub fn lock_work_dir(mode: LockMode) -> Result<Option<Lock>> {
+    let path = get_state_dir()?.join(WORK_DIR_LOCK_FILE);
+    match mode {
+        LockMode::None => Ok(None),
+        LockMode::Shared => acquire(&path, false, "using the working directory").map(Some),
+        LockMode::Exclusive => acquire(&path, true, "using the working directory").map(Some),
+    }
+}
+
+/// Locks the installation of the given [`Version`], so that
+/// concurrent installations of the same SDK wait for each other.
+pub fn lock_install(version: &Version) -> Result<Lock> {
+    acquire(
+        &get_install_lock_file(version)?,
+        true,
+        &format!("installing SDK {version}"),
+    )
+}
+
+/// Tries to lock the installation of the given [`Version`]
+/// without waiting. Returns [`None`] if the SDK is currently
+/// being installed by another goup process.
+pub fn try_lock_install(version: &Version) -> Result<Option<Lock>> {
+    try_acquire(&get_install_lock_file(version)?, true)
+}
+
+/// Locks the state file while it is read and written back.
+pub fn lock_state() -> Result<Lock> {
+    acquire(
+        &get_state_dir()?.join(STATE_LOCK_FILE),
+        true,
+        "writing the state file",
+    )
+}
+
+/// Locks the selection of the current SDK while the link to it
+/// and the state file are updated, so that other processes never
+/// observe or repair a half-finished selection change.
+pub fn lock_selection() -> Result<Lock> {
+    acquire(
+        &get_state_dir()?.join(SELECTION_LOCK_FILE),
+        true,
+        "changing the selected SDK",
+    )
+}
+
+/// Returns the lock file for installations of the
+/// given [`Version`].
+///
+/// # Example
+/// ```
+/// let file = get_install_lock_file(&"1.22.3".parse().unwrap()).unwrap();
+/// // -> "/home/me/.local/goup/locks/1.22.3.lock"
+/// ```
+fn get_install_lock_file(version: &Version) -> Result<PathBuf> {
+    get_state_dir().map(|dir| dir.join("locks").join(format!("{version}.lock")))
+}
+
+/// Acquires a lock on the given file. If the file is locked by
+/// another process, the lock is retried until the configured
+/// timeout is exceeded.
+///
+/// `action` describes what the other process is doing and is
+/// used in messages to the user.
+fn acquire(path: &Path, exclusive: bool, action: &str) -> Result<Lock> {
+    acquire_within(path, exclusive, action, get_config().lock_timeout())
+}
+
+/// Tries to acquire a lock on the given file without waiting.
+/// Returns [`None`] if the file is locked by another process.
+fn try_acquire(path: &Path, exclusive: bool) -> Result<Option<Lock>> {
+    let file = open_lock_file(path)?;
+    match try_lock(&file, exclusive) {
+        Ok(_) => Ok(Some(Lock { file })),
+        Err(err) if is_contended(&err) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn acquire_within(path: &Path, exclusive: bool, action: &str, timeout: Duration) -> Result<Lock> {
+    let file = open_lock_file(path)?;
+    let start = Instant::now();
+    let mut waiting = false;
+
+    loop {
+        match try_lock(&file, exclusive) {
+            Ok(_) => return Ok(Lock { file }),
+            Err(err) if !is_contended(&err) => return Err(err.into()),
+            Err(_) if start.elapsed() >= timeout => anyhow::bail!(
+                "Timed out after {} waiting for another goup process {action}.\n\
+                Try again later or increase `{LOCK_TIMEOUT}` (see `goup help config`).",
+                humantime::format_duration(timeout)
+            ),
+            Err(_) => {
+                if !waiting {
+                    tui::eprint_note(&format!("Waiting for another goup process {action} ..."));
+                    waiting = true;
+                }
+                thread::sleep(RETRY_INTERVAL);
+            }
+        }
+    }
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    if let Some(parent) = path.parent() {
+        ensure_dir(parent)?;
+    }
+
+    Ok(fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?)
+}
+
+fn try_lock(file: &File, exclusive: bool) -> io::Result<()> {
+    if exclusive {
+        FileExt::try_lock_exclusive(file)
+    } else {
+        FileExt::try_lock_shared(file)
+    }
+}
+
+fn is_contended(err: &io::Error) -> bool {
+    err.raw_os_error() == fs4::lock_contended_error().raw_os_error()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_contention() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("locks").join("test.lock");
+
+        let shared = try_acquire(&path, false).unwrap().unwrap();
+        assert!(try_acquire(&path, false).unwrap().is_some());
+        assert!(try_acquire(&path, true).unwrap().is_none());
+
+        drop(shared);
+        let exclusive = try_acquire(&path, true).unwrap().unwrap();
+        assert!(try_acquire(&path, false).unwrap().is_none());
+        assert!(try_acquire(&path, true).unwrap().is_none());
+
+        drop(exclusive);
+        assert!(try_acquire(&path, true).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("test.lock");
+        let timeout = Duration::from_millis(250);
+
+        let shared = acquire_within(&path, false, "testing", timeout).unwrap();
+        assert!(acquire_within(&path, false, "testing", timeout).is_ok());
+
+        let start = Instant::now();
+        let err = acquire_within(&path, true, "testing", timeout)
+            .err()
+            .unwrap();
+        assert!(start.elapsed() >= timeout);
+        assert!(err.to_string().starts_with("Timed out after 250ms"));
+
+        drop(shared);
+        assert!(acquire_within(&path, true, "testing", timeout).is_ok());
+    }
+}
@@ -0,0 +1,277 @@
+use super::*;
+use crate::versions::Version;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// The directory in the working directory where manifests
+/// of installed SDKs are stored.
+const MANIFESTS_DIR: &str = "manifests";
+
+/// A recorded file of an installed SDK.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ManifestEntry {
+    /// The size of the file in bytes.
+    pub size: u64,
+
+    /// The hex encoded SHA-256 checksum of the file contents
+    /// or, for symlinks, of the link target.
+    pub sha256: String,
+}
+
+/// The list of files of an installed SDK recorded at install
+/// time, used to detect modified or deleted files later on.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Manifest {
+    /// The files by their path relative to the installation
+    /// directory, separated with `/`.
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// A difference between a [`Manifest`] and the files
+/// currently present in an installation directory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ManifestDiff {
+    /// The file has been deleted.
+    Missing(String),
+
+    /// The size or contents of the file have changed.
+    Changed(String),
+
+    /// The file has not been recorded.
+    Added(String),
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "missing: {path}"),
+            Self::Changed(path) => write!(f, "changed: {path}"),
+            Self::Added(path) => write!(f, "added:   {path}"),
+        }
+    }
+}
+
+impl Manifest {
+    /// Creates a manifest of all files in the given directory.
+    pub fn create<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let mut manifest = Self::default();
+        manifest.add_dir(dir.as_ref(), "")?;
+        Ok(manifest)
+    }
+
+    fn add_dir(&mut self, dir: &Path, prefix: &str) -> Result<()> {
+        for entry in dir.read_dir()? {
+            let entry = entry?;
+            let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() {
+                self.add_dir(&path, &format!("{name}/"))?;
+                continue;
+            }
+
+            self.files.insert(name, hash_file(&path)?);
+        }
+        Ok(())
+    }
+
+    /// Compares this manifest against another one, typically
+    /// created from the current state of the installation.
+    ///
+    /// Differences are returned ordered by path.
+    pub fn diff(&self, actual: &Manifest) -> Vec<ManifestDiff> {
+        let mut diffs = vec![];
+
+        for (path, entry) in &self.files {
+            match actual.files.get(path) {
+                None => diffs.push(ManifestDiff::Missing(path.clone())),
+                Some(e) if e != entry => diffs.push(ManifestDiff::Changed(path.clone())),
+                Some(_) => {}
+            }
+        }
+
+        diffs.extend(
+            actual
+                .files
+                .keys()
+                .filter(|path| !self.files.contains_key(*path))
+                .map(|path| ManifestDiff::Added(path.clone())),
+        );
+
+        diffs.sort_by(|a, b| diff_path(a).cmp(diff_path(b)));
+        diffs
+    }
+}
+
+fn diff_path(diff: &ManifestDiff) -> &str {
+    match diff {
+        ManifestDiff::Missing(p) | ManifestDiff::Changed(p) | ManifestDiff::Added(p) => p,
+    }
+}
+
+/// Hashes the file at the given path. Symlinks are not
+/// followed, their target is hashed instead.
+pub(super) fn hash_file(path: &Path) -> Result<ManifestEntry> {
+    let meta = fs::symlink_metadata(path)?;
+    let mut hasher = Sha256::new();
+
+    if meta.is_symlink() {
+        hasher.update(fs::read_link(path)?.to_string_lossy().as_bytes());
+    } else {
+        io::copy(&mut BufReader::new(fs::File::open(path)?), &mut hasher)?;
+    }
+
+    Ok(ManifestEntry {
+        size: meta.len(),
+        sha256: hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect(),
+    })
+}
+
+/// Returns the directory where manifests of installed SDKs are stored.
+///
+/// # Example
+/// ```
+/// let dir = get_manifests_dir().unwrap();
+/// // -> "/home/me/.local/goup/manifests"
+/// ```
+pub fn get_manifests_dir() -> Result<PathBuf> {
+    get_work_dir().map(|dir| dir.join(MANIFESTS_DIR))
+}
+
+/// Returns the path of the manifest file of the given [`Version`].
+///
+/// # Example
+/// ```
+/// let file = get_manifest_file(&"1.22.3".parse().unwrap()).unwrap();
+/// // -> "/home/me/.local/goup/manifests/1.22.3.json"
+/// ```
+pub fn get_manifest_file(version: &Version) -> Result<PathBuf> {
+    get_manifests_dir().map(|dir| dir.join(format!("{version}.json")))
+}
+
+/// Reads the manifest of the given [`Version`], if
+/// it has been recorded.
+pub fn read_manifest(version: &Version) -> Result<Option<Manifest>> {
+    match fs::read_to_string(get_manifest_file(version)?) {
+        Ok(v) => Ok(Some(serde_json::from_str(&v)?)),
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Writes the manifest of the given [`Version`].
+pub fn write_manifest(version: &Version, manifest: &Manifest) -> Result<()> {
+    let file = get_manifest_file(version)?;
+    ensure_dir(file.parent().expect("manifest file has a parent"))?;
+
+    let tmp = get_temp_path(&file);
+    fs::write(&tmp, serde_json::to_vec(manifest)?)?;
+    fs::rename(tmp, file)?;
+
+    Ok(())
+}
+
+/// Removes the manifest of the given [`Version`], if any.
+pub fn remove_manifest(version: &Version) -> Result<()> {
+    match fs::remove_file(get_manifest_file(version)?) {
+        Err(err) if !matches!(err.kind(), io::ErrorKind::NotFound) => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Compares the installed SDK of the given [`Version`] against
+/// its recorded manifest.
+///
+/// Returns [`None`] if no manifest has been recorded.
+pub fn verify_installation(version: &Version) -> Result<Option<Vec<ManifestDiff>>> {
+    let Some(manifest) = read_manifest(version)? else {
+        return Ok(None);
+    };
+
+    let dir = get_version_installation_dir(version)?;
+    let actual = match dir.is_dir() {
+        true => Manifest::create(dir)?,
+        false => Manifest::default(),
+    };
+
+    Ok(Some(manifest.diff(&actual)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manifest(files: &[(&str, u64, &str)]) -> Manifest {
+        Manifest {
+            files: files
+                .iter()
+                .map(|(path, size, sha256)| {
+                    (
+                        path.to_string(),
+                        ManifestEntry {
+                            size: *size,
+                            sha256: sha256.to_string(),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let recorded = manifest(&[
+            ("go/VERSION", 1, "a"),
+            ("go/bin/go", 2, "b"),
+            ("go/c", 3, "c"),
+        ]);
+        let actual = manifest(&[
+            ("go/VERSION", 1, "a"),
+            ("go/bin/go", 2, "x"),
+            ("go/d", 4, "d"),
+        ]);
+
+        assert_eq!(
+            vec![
+                ManifestDiff::Changed("go/bin/go".into()),
+                ManifestDiff::Missing("go/c".into()),
+                ManifestDiff::Added("go/d".into()),
+            ],
+            recorded.diff(&actual)
+        );
+        assert!(recorded.diff(&recorded).is_empty());
+    }
+
+    #[test]
+    fn test_create() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("go/bin")).unwrap();
+        fs::write(dir.path().join("go/bin/go"), "binary").unwrap();
+        fs::write(dir.path().join("go/VERSION"), "go1.22.3").unwrap();
+
+        let m = Manifest::create(dir.path()).unwrap();
+        assert_eq!(
+            vec!["go/VERSION", "go/bin/go"],
+            m.files.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(6, m.files["go/bin/go"].size);
+
+        fs::write(dir.path().join("go/bin/go"), "modified").unwrap();
+        assert_eq!(
+            vec![ManifestDiff::Changed("go/bin/go".into())],
+            m.diff(&Manifest::create(dir.path()).unwrap())
+        );
+    }
+}
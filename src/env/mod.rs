@@ -11,4 +11,25 @@ pub use windows::*;
 mod shared;
 pub use shared::*;
 
+mod goroot;
+pub use goroot::*;
+
+mod state;
+pub use state::*;
+
+mod lock;
+pub use lock::*;
+
+mod history;
+pub use history::*;
+
+mod manifest;
+pub use manifest::*;
+
+mod disk;
+pub use disk::*;
+
+mod store;
+pub use store::*;
+
 pub mod download;
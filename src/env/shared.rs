@@ -1,4 +1,14 @@
-use crate::{shell::ShellEnv, versions::Version, warning};
+use super::{
+    append_history, get_available_versions, get_manifests_dir, get_sdk_dir, get_state_file,
+    link_current_version, lock_selection, now, remove_manifest, write_launcher, HistoryEntry,
+    State,
+};
+use crate::{
+    config::get_config,
+    shell::ShellEnv,
+    versions::{Channel, Version},
+    warning,
+};
 use anyhow::Result;
 use directories::UserDirs;
 use std::{
@@ -8,19 +18,18 @@ use std::{
 };
 use whattheshell::Shell;
 
-/// The file where the currently selected version is
-/// written into.
-const CURRENT_VERSION_FILE: &str = ".current_version";
-
 /// Returns all required environment variables.
 pub fn get_env_vars(shell: &Shell) -> Result<String> {
     let path = std::env::var("PATH")?;
 
+    let bin_dirs = [
+        shell.path_to_string(get_current_bin_dir()?)?,
+        shell.path_to_string(get_versioned_bin_dir()?)?,
+    ]
+    .join(shell.get_path_separator());
+
     let vars = [
-        (
-            "PATH",
-            shell.append_to_path(&path, &shell.path_to_string(get_current_bin_dir()?)?)?,
-        ),
+        ("PATH", shell.append_to_path(&path, &bin_dirs)?),
         ("GOROOT", shell.path_to_string(get_current_install_dir()?)?),
     ];
 
@@ -47,9 +56,15 @@ pub fn get_home_dir() -> Result<PathBuf> {
 
 /// Returns the current working directory.
 ///
-/// This directory contains all status files,
-/// installed SDKs and the symlink to the
-/// currently selected SDK.
+/// This directory contains installed SDKs and the
+/// symlink to the currently selected SDK. Unless the
+/// XDG layout is enabled, it also contains all status
+/// files and cached data.
+///
+/// The directory can be set via the `--home` flag,
+/// the `GOUP_HOME` environment variable or the `home`
+/// configuration key. Otherwise, if the XDG layout is
+/// enabled, `$XDG_DATA_HOME/goup` is used.
 ///
 /// # Example
 /// ```
@@ -57,9 +72,49 @@ pub fn get_home_dir() -> Result<PathBuf> {
 /// // -> "/home/me/.local/goup"
 /// ```
 pub fn get_work_dir() -> Result<PathBuf> {
+    let config = get_config();
+
+    if let Some(home) = config.home() {
+        return Ok(std::path::absolute(home)?);
+    }
+
+    if config.xdg() {
+        return get_xdg_dir("XDG_DATA_HOME", &[".local", "share"]);
+    }
+
     get_home_dir().map(|dir| dir.join(".local").join("goup"))
 }
 
+/// Returns the directory containing goup's state, like the
+/// currently selected version and the state file.
+///
+/// This is the working directory *(see [`get_work_dir`])*,
+/// unless the XDG layout is enabled and no explicit home
+/// directory is set. Then, `$XDG_STATE_HOME/goup` is used.
+///
+/// # Example
+/// ```
+/// let dir = get_state_dir().unwrap();
+/// // -> "/home/me/.local/state/goup"
+/// ```
+pub fn get_state_dir() -> Result<PathBuf> {
+    match get_config() {
+        c if c.xdg() && c.home().is_none() => get_xdg_dir("XDG_STATE_HOME", &[".local", "state"]),
+        _ => get_work_dir(),
+    }
+}
+
+/// Returns the `goup` directory in the XDG base directory set
+/// via the given environment variable or, if not set, in the
+/// given default location relative to the users home directory.
+fn get_xdg_dir(var: &str, default: &[&str]) -> Result<PathBuf> {
+    let base = match std::env::var_os(var).filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => default.iter().fold(get_home_dir()?, |dir, p| dir.join(p)),
+    };
+    Ok(base.join("goup"))
+}
+
 /// Returns the SDK installations dirrectory.
 ///
 /// This directory contains all installed
@@ -123,6 +178,61 @@ pub fn get_version_installation_dir(version: &Version) -> Result<PathBuf> {
     get_installations_dir().map(|v| v.join(version.to_string()))
 }
 
+/// Returns the directory containing launchers for all
+/// installed SDKs named after their version (like `go1.22.3`).
+///
+/// # Example
+/// ```
+/// let dir = get_versioned_bin_dir().unwrap();
+/// // -> "/home/me/.local/goup/bin"
+/// ```
+pub fn get_versioned_bin_dir() -> Result<PathBuf> {
+    get_work_dir().map(|dir| dir.join("bin"))
+}
+
+/// Returns the directory containing the Go source
+/// repository used to build SDKs from source.
+///
+/// # Example
+/// ```
+/// let dir = get_source_dir().unwrap();
+/// // -> "/home/me/.local/goup/source"
+/// ```
+pub fn get_source_dir() -> Result<PathBuf> {
+    get_work_dir().map(|dir| dir.join("source"))
+}
+
+/// Returns the directory where SDKs are built from
+/// source before being moved into the installations
+/// directory.
+///
+/// # Example
+/// ```
+/// let dir = get_builds_dir().unwrap();
+/// // -> "/home/me/.local/goup/builds"
+/// ```
+pub fn get_builds_dir() -> Result<PathBuf> {
+    get_work_dir().map(|dir| dir.join("builds"))
+}
+
+/// Returns the directory where data fetched from
+/// remote sources is cached.
+///
+/// If the XDG layout is enabled and no explicit home
+/// directory is set, `$XDG_CACHE_HOME/goup` is used.
+///
+/// # Example
+/// ```
+/// let dir = get_cache_dir().unwrap();
+/// // -> "/home/me/.local/goup/cache"
+/// ```
+pub fn get_cache_dir() -> Result<PathBuf> {
+    match get_config() {
+        c if c.xdg() && c.home().is_none() => get_xdg_dir("XDG_CACHE_HOME", &[".cache"]),
+        _ => get_work_dir().map(|dir| dir.join("cache")),
+    }
+}
+
 /// Checks if the passed directory exists and
 /// tries to create it if it does not exist.
 pub fn ensure_dir<P: AsRef<Path>>(path: P) -> Result<()> {
@@ -132,31 +242,109 @@ pub fn ensure_dir<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
+/// Returns a unique path next to the given `path` which can
+/// be used to prepare a file before renaming it to `path`.
+///
+/// # Example
+/// ```
+/// let path = get_temp_path("/home/me/.local/goup/current");
+/// // -> "/home/me/.local/goup/.current.tmp-1234"
+/// ```
+pub fn get_temp_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.tmp-{}", std::process::id()))
+}
+
 /// Tries to read the currently selected [`Version`] from
-/// the workdir location.
+/// the state file *(see [`get_state_file`])*.
 ///
 /// If no version has been selected, [`None`] is returend.
 pub fn get_current_version() -> Result<Option<Version>> {
-    let vfile_path = get_work_dir()?.join(CURRENT_VERSION_FILE);
-    let mut vfile = match File::open(vfile_path) {
-        Ok(f) => f,
-        Err(err) => {
-            if matches!(err.kind(), std::io::ErrorKind::NotFound) {
-                return Ok(None);
-            } else {
-                return Err(err.into());
+    State::load()?.current.map(|v| v.parse()).transpose()
+}
+
+/// Returns the [`Version`] the symlink to the currently
+/// selected SDK points to, if the link exists and points
+/// to an installed SDK.
+pub fn get_linked_version() -> Result<Option<Version>> {
+    let target = match fs::read_link(get_current_link_dir()?) {
+        Ok(v) => v,
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let version = target
+        .file_name()
+        .and_then(|name| name.to_string_lossy().parse::<Version>().ok())
+        .filter(|v| get_sdk_dir(v).is_ok_and(|dir| dir == target))
+        .filter(|_| target.is_dir());
+
+    Ok(version)
+}
+
+/// Checks if the currently selected version recorded in the
+/// state file matches the target of the symlink to the current
+/// SDK and repairs both if they disagree.
+///
+/// The state file takes precedence as long as the recorded SDK
+/// is installed or provided by the system store. Otherwise, the
+/// selection is taken over from the link, if it points to an
+/// available SDK, or unset.
+///
+/// The selection lock is held during the repair *(see
+/// [`lock_selection`])*, so that selection changes of other
+/// processes are not mistaken for inconsistencies.
+///
+/// Returns a description of the performed repair, if any.
+pub fn repair_current_selection() -> Result<Option<String>> {
+    let link = get_current_link_dir()?;
+    if fs::symlink_metadata(&link).is_err() && !get_state_file()?.exists() {
+        return Ok(None);
+    }
+
+    let _lock = lock_selection()?;
+    let link_exists = fs::symlink_metadata(&link).is_ok();
+
+    let installed = get_available_versions()?;
+    let recorded = get_current_version()?;
+    let linked = get_linked_version()?;
+
+    let repair = match (recorded, linked) {
+        (Some(r), Some(l)) if r == l => return Ok(None),
+        (None, None) if !link_exists => return Ok(None),
+        (Some(r), _) if installed.contains(&r) => {
+            link_current_version(Some(&r))?;
+            format!("The link to the selected SDK {r} has been restored.")
+        }
+        (recorded, Some(l)) => {
+            write_current_version(Some(&l))?;
+            match recorded {
+                Some(r) => {
+                    format!("The selected SDK {r} is not installed anymore, switched to {l}.")
+                }
+                None => format!("The selection of SDK {l} has been restored."),
+            }
+        }
+        (recorded, None) => {
+            link_current_version(None)?;
+            write_current_version(None)?;
+            match recorded {
+                Some(r) => format!(
+                    "The selected SDK {r} is not installed anymore and has been unselected."
+                ),
+                None => "A broken link to the selected SDK has been removed.".into(),
             }
         }
     };
 
-    let mut ver = String::new();
-    vfile.read_to_string(&mut ver)?;
-
-    let v = ver.parse()?;
-    Ok(Some(v))
+    Ok(Some(repair))
 }
 
 /// Returns a list of all installed SDK versions.
+///
+/// Hidden entries are skipped, which are temporary directories
+/// of SDKs being reinstalled *(see [`get_temp_path`])*.
 pub fn get_installed_versions() -> Result<Vec<Version>> {
     let dir = match get_installations_dir()?.read_dir() {
         Ok(v) => v,
@@ -167,22 +355,124 @@ pub fn get_installed_versions() -> Result<Vec<Version>> {
     let dir: Result<Vec<_>, _> = dir.collect();
     let versions: Result<Vec<Version>, _> = dir?
         .iter()
-        .map(|v| v.file_name().to_string_lossy().parse())
+        .map(|v| v.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .map(|name| name.parse())
         .collect();
 
     versions
 }
 
-/// Writes the given [`Version`] to the working directory.
+/// Returns whether the SDK of the given [`Version`] is completely
+/// installed, which is the case when it has been recorded in the
+/// state file and its installation directory exists.
+pub fn is_installed(version: &Version) -> Result<bool> {
+    Ok(State::load()?.sdk(version).is_some() && get_version_installation_dir(version)?.is_dir())
+}
+
+/// Selects the given [`Version`] by linking it *(see
+/// [`link_current_version`])* and writing it to the state
+/// file *(see [`write_current_version`])*.
+///
+/// The selection lock is held while doing so *(see
+/// [`lock_selection`])*, so that both are changed together.
+/// If [`None`] is passed, the current version is unset.
+pub fn set_current_version(version: Option<&Version>) -> Result<()> {
+    let _lock = lock_selection()?;
+    link_current_version(version)?;
+    write_current_version(version)
+}
+
+/// Writes the given [`Version`] to the state file.
 ///
 /// If [`Some(Version)`] is passed, the passed [`Version`] is set.
 /// if [`None`] is passed, the current version will be unset.
+///
+/// The time of last usage is recorded for the previously and
+/// the newly selected version and the change is appended to
+/// the history *(see [`append_history`])*. When the current
+/// version is unset, the tracked release channel is unset
+/// as well.
 pub fn write_current_version(version: Option<&Version>) -> Result<()> {
-    let vfile_path = get_work_dir()?.join(CURRENT_VERSION_FILE);
-    match version {
-        Some(v) => File::create(vfile_path)?.write_all(v.to_string().as_bytes())?,
-        None => fs::remove_file(vfile_path)?,
+    State::modify(|state| {
+        let now = now();
+        for v in state
+            .current
+            .iter()
+            .cloned()
+            .chain(version.map(|v| v.to_string()))
+        {
+            if let Some(sdk) = state.sdks.get_mut(&v) {
+                sdk.last_used = Some(now);
+            }
+        }
+        let previous = state.current.take();
+        state.current = version.map(|v| v.to_string());
+        if version.is_none() {
+            state.channel = None;
+        }
+        if previous != state.current {
+            append_history(&HistoryEntry::new(previous, state.current.clone()))?;
+        }
+        Ok(())
+    })
+}
+
+/// Returns the release [`Channel`] which is tracked by the
+/// current selection, if any.
+pub fn get_current_channel() -> Result<Option<Channel>> {
+    State::load()?
+        .channel
+        .map(|c| {
+            c.parse()
+                .map_err(|err| anyhow::anyhow!("invalid channel in state file: {err}"))
+        })
+        .transpose()
+}
+
+/// Writes the release [`Channel`] which is tracked by the
+/// current selection to the state file.
+///
+/// If [`None`] is passed, the selection does not track
+/// any channel.
+pub fn write_current_channel(channel: Option<&Channel>) -> Result<()> {
+    State::modify(|state| {
+        state.channel = channel.map(|c| c.to_string());
+        Ok(())
+    })
+}
+
+/// Writes a launcher named after the version *(like `go1.22.3`)*
+/// into the versioned bin directory *(see [`get_versioned_bin_dir`])*
+/// for every installed release SDK, including those provided by
+/// the system store, and removes launchers of SDKs which are not
+/// available anymore.
+///
+/// This allows the Go command to use goup's SDKs when switching
+/// toolchains via `GOTOOLCHAIN` instead of downloading them.
+pub fn sync_versioned_binaries() -> Result<()> {
+    let dir = get_versioned_bin_dir()?;
+    ensure_dir(&dir)?;
+
+    let names: Vec<_> = get_available_versions()?
+        .iter()
+        .filter(|v| !v.is_ident())
+        .map(|v| (format!("go{v}"), v.clone()))
+        .collect();
+
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let name = file_name.strip_suffix(".cmd").unwrap_or(&file_name);
+        if !names.iter().any(|(n, _)| n == name) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    for (name, version) in names {
+        write_launcher(dir.join(name), get_sdk_dir(&version)?.join("go"))?;
     }
+
     Ok(())
 }
 
@@ -190,7 +480,12 @@ pub fn write_current_version(version: Option<&Version>) -> Result<()> {
 pub fn drop_version(version: &Version) -> Result<()> {
     let dir = get_version_installation_dir(version)?;
     fs::remove_dir_all(dir)?;
-    Ok(())
+    remove_manifest(version)?;
+
+    State::modify(|state| {
+        state.sdks.remove(&version.to_string());
+        Ok(())
+    })
 }
 
 /// Deletes the installation drirectory *(see [`get_installations_dir`])*
@@ -198,7 +493,16 @@ pub fn drop_version(version: &Version) -> Result<()> {
 pub fn drop_install_dir() -> Result<()> {
     let dir = get_installations_dir()?;
     fs::remove_dir_all(dir)?;
-    Ok(())
+
+    match fs::remove_dir_all(get_manifests_dir()?) {
+        Err(err) if !matches!(err.kind(), io::ErrorKind::NotFound) => return Err(err.into()),
+        _ => {}
+    }
+
+    State::modify(|state| {
+        state.sdks.clear();
+        Ok(())
+    })
 }
 
 /// Reads and returns the content of the profile file in
@@ -242,6 +546,15 @@ pub fn append_to_profile(shell: &Shell, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Overwrites the profile file in the current users `$HOME`
+/// directory with the given `content`.
+///
+/// See [`get_profile_dir`] for more information.
+pub fn write_profile(shell: &Shell, content: &str) -> Result<()> {
+    fs::write(shell.get_profile_dir()?, content)?;
+    Ok(())
+}
+
 /// Checks if the `GOROOT` environment variable is applied and prints a
 /// warning message to the terminal if not.
 pub fn check_env_applied(shell: &Shell) -> Result<()> {
@@ -283,6 +596,20 @@ pub fn to_gitbash_path_var(curr: &str) -> String {
 #[cfg(test)]
 mod test {
 
+    #[test]
+    fn test_get_temp_path() {
+        use super::*;
+
+        let path = get_temp_path("/home/me/.local/goup/current");
+        assert_eq!(
+            PathBuf::from(format!(
+                "/home/me/.local/goup/.current.tmp-{}",
+                std::process::id()
+            )),
+            path
+        );
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_to_gitbash_path() {
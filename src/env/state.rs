@@ -0,0 +1,459 @@
+use super::{download::*, *};
+use crate::{
+    profile::{self, InstallProfile},
+    versions::Version,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The file where goup's persistent state is stored.
+const STATE_FILE: &str = "state.json";
+
+/// The file where the currently selected version has been
+/// stored before the introduction of the state file.
+const LEGACY_CURRENT_VERSION_FILE: &str = ".current_version";
+
+/// The current version of the state file schema. It must be
+/// increased with every incompatible change of [`State`] and
+/// a migration must be added to [`MIGRATIONS`].
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// A migration of the raw state to the next schema version.
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// Migrations of the raw state, where the migration at index
+/// `n` upgrades the state from schema version `n` to `n + 1`.
+const MIGRATIONS: &[Migration] = &[migrate_v0];
+
+/// Persistent state of goup which is stored in the
+/// state directory *(see [`get_state_dir`])*.
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    /// Version of the schema the state has been written with.
+    pub schema: u64,
+
+    /// The currently selected SDK version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+
+    /// The release channel the current selection tracks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+
+    /// User defined aliases mapping alias names to
+    /// version selectors.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+
+    /// Metadata of installed SDKs keyed by version.
+    #[serde(default)]
+    pub sdks: BTreeMap<String, Sdk>,
+}
+
+/// Describes how an SDK has been installed.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    /// Downloaded from the download mirror.
+    Downloaded,
+    /// Linked to an SDK outside of the work directory.
+    Linked,
+    /// Copied or moved from another location.
+    Imported,
+    /// Built from source.
+    Built,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Downloaded => write!(f, "downloaded"),
+            Self::Linked => write!(f, "linked"),
+            Self::Imported => write!(f, "imported"),
+            Self::Built => write!(f, "built"),
+        }
+    }
+}
+
+/// Metadata of an installed SDK.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Sdk {
+    /// The version the SDK reports itself.
+    pub version: String,
+
+    /// The platform the SDK has been installed for
+    /// *(like `linux-amd64`)*.
+    pub platform: String,
+
+    /// Where the SDK has been installed from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+
+    /// SHA-256 checksum of the downloaded archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Unix timestamp of the installation.
+    pub installed_at: u64,
+
+    /// Unix timestamp of when the SDK has been used last.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<u64>,
+
+    /// How the SDK has been installed.
+    pub origin: Origin,
+
+    /// Whether the SDK is protected from removal.
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// The install profile, if not all files of the SDK have
+    /// been installed *(like `minimal`)*.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Patterns of the paths included by the install profile,
+    /// as resolved when installing the SDK.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub included: Vec<String>,
+
+    /// Patterns of the paths excluded by the install profile,
+    /// as resolved when installing the SDK.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            schema: SCHEMA_VERSION,
+            current: None,
+            channel: None,
+            aliases: BTreeMap::new(),
+            sdks: BTreeMap::new(),
+        }
+    }
+}
+
+impl State {
+    /// Reads the state from the state directory.
+    ///
+    /// If the state has been written with an older schema, it
+    /// is migrated and written back. If no state has been stored
+    /// yet, the state is initialized from the installed SDKs.
+    pub fn load() -> Result<Self> {
+        let raw = match fs::read_to_string(get_state_file()?) {
+            Ok(v) => serde_json::from_str(&v)
+                .map_err(|err| anyhow::anyhow!("failed parsing state file: {err}"))?,
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => Map::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let (state, migrated) = Self::from_raw(raw)?;
+        if migrated {
+            state.save()?;
+            let legacy_file = get_state_dir()?.join(LEGACY_CURRENT_VERSION_FILE);
+            if legacy_file.exists() {
+                fs::remove_file(legacy_file)?;
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Reads the state stored in the given goup home directory
+    /// without migrating or initializing it, because it may be
+    /// read-only for the current user *(like the system store)*.
+    pub fn read_from(dir: &Path) -> Result<Self> {
+        let file = dir.join(STATE_FILE);
+        let content = match fs::read_to_string(&file) {
+            Ok(v) => v,
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let state: Self = serde_json::from_str(&content)
+            .map_err(|err| anyhow::anyhow!("failed parsing {}: {err}", file.display()))?;
+        if state.schema != SCHEMA_VERSION {
+            anyhow::bail!(
+                "{} has been written by another version of goup (schema version {}).",
+                file.display(),
+                state.schema
+            );
+        }
+
+        Ok(state)
+    }
+
+    /// Writes the state to the state directory.
+    ///
+    /// The state is written to a temporary file first, which
+    /// then replaces the state file, so that the state file is
+    /// never left partially written.
+    pub fn save(&self) -> Result<()> {
+        ensure_dir(get_state_dir()?)?;
+        let content = serde_json::to_string_pretty(self)?;
+        let file = get_state_file()?;
+        let tmp_file = get_temp_path(&file);
+        fs::write(&tmp_file, content)?;
+        fs::rename(tmp_file, file)?;
+        Ok(())
+    }
+
+    /// Loads the state, applies `f` to it and writes it back
+    /// while holding the state lock *(see [`lock_state`])*, so
+    /// that concurrent modifications are not lost.
+    pub fn modify<T>(f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let _lock = lock_state()?;
+        let mut state = Self::load()?;
+        let res = f(&mut state)?;
+        state.save()?;
+        Ok(res)
+    }
+
+    /// Returns the metadata of the given SDK, if recorded.
+    pub fn sdk(&self, version: &Version) -> Option<&Sdk> {
+        self.sdks.get(&version.to_string())
+    }
+
+    /// Migrates the given raw state to the current schema and
+    /// deserializes it. Returns whether a migration took place.
+    fn from_raw(mut raw: Map<String, Value>) -> Result<(Self, bool)> {
+        let schema = raw.get("schema").and_then(Value::as_u64).unwrap_or(0);
+        if schema > SCHEMA_VERSION {
+            anyhow::bail!(
+                "The state file has been written by a newer version of goup \
+                (schema version {schema}). Please update goup."
+            );
+        }
+
+        for migration in &MIGRATIONS[schema as usize..] {
+            migration(&mut raw)?;
+        }
+        raw.insert("schema".into(), SCHEMA_VERSION.into());
+
+        let state = serde_json::from_value(Value::Object(raw))
+            .map_err(|err| anyhow::anyhow!("failed parsing state file: {err}"))?;
+
+        Ok((state, schema < SCHEMA_VERSION))
+    }
+}
+
+impl Sdk {
+    /// Creates metadata for an SDK of the given [`Version`]
+    /// installed right now for the current platform.
+    pub fn new(version: &Version, origin: Origin) -> Self {
+        Self {
+            version: version.to_string(),
+            platform: format!("{GOOS}-{GOARCH}"),
+            source_url: None,
+            sha256: None,
+            installed_at: now(),
+            last_used: None,
+            origin,
+            pinned: false,
+            profile: None,
+            included: vec![],
+            excluded: vec![],
+        }
+    }
+
+    /// Derives metadata for the installed SDK of the given
+    /// [`Version`] from its installation directory, for SDKs
+    /// which have not been recorded when installing them.
+    pub fn from_installation(version: &Version) -> Result<Self> {
+        let dir = get_version_installation_dir(version)?;
+        let origin = if fs::symlink_metadata(dir.join("go")).is_ok_and(|m| m.is_symlink()) {
+            Origin::Linked
+        } else if version.is_ident() {
+            Origin::Built
+        } else {
+            Origin::Downloaded
+        };
+        let installed_at = fs::metadata(&dir)?
+            .modified()?
+            .duration_since(UNIX_EPOCH)?
+            .as_secs();
+
+        Ok(Self {
+            installed_at,
+            ..Self::new(version, origin)
+        })
+    }
+}
+
+/// Records the metadata of a newly installed SDK of the given
+/// [`Version`] in the state file.
+///
+/// When an SDK is replaced, its usage time and pin status
+/// are kept.
+pub fn register_sdk(version: &Version, mut sdk: Sdk) -> Result<()> {
+    State::modify(|state| {
+        if let Some(prev) = state.sdk(version) {
+            sdk.last_used = prev.last_used;
+            sdk.pinned = prev.pinned;
+        }
+        state.sdks.insert(version.to_string(), sdk);
+        Ok(())
+    })
+}
+
+/// Returns the install profile the SDK of the given [`Version`]
+/// has been installed with, using the patterns recorded at
+/// install time. If no patterns have been recorded, the profile
+/// is resolved from the current configuration.
+pub fn get_sdk_profile(version: &Version) -> Result<InstallProfile> {
+    let state = State::load()?;
+    let Some((sdk, name)) = state
+        .sdk(version)
+        .and_then(|sdk| sdk.profile.as_ref().map(|name| (sdk, name)))
+    else {
+        return InstallProfile::new(profile::FULL);
+    };
+
+    match sdk.excluded.is_empty() {
+        true => InstallProfile::new(name),
+        false => InstallProfile::with_patterns(name, &sdk.included, &sdk.excluded),
+    }
+}
+
+/// Returns whether the SDK of the given [`Version`] is pinned.
+pub fn is_pinned(version: &Version) -> Result<bool> {
+    Ok(State::load()?.sdk(version).is_some_and(|sdk| sdk.pinned))
+}
+
+/// Returns the versions of all pinned SDKs.
+pub fn get_pinned_versions() -> Result<Vec<Version>> {
+    State::load()?
+        .sdks
+        .iter()
+        .filter(|(_, sdk)| sdk.pinned)
+        .map(|(v, _)| v.parse())
+        .collect()
+}
+
+/// Pins or unpins the SDK of the given [`Version`]. Pinned SDKs
+/// are never removed automatically and a pinned selection is
+/// only changed on explicit request.
+pub fn set_pinned(version: &Version, pinned: bool) -> Result<()> {
+    State::modify(|state| {
+        let sdk = match state.sdks.entry(version.to_string()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(Sdk::from_installation(version)?),
+        };
+        sdk.pinned = pinned;
+        Ok(())
+    })
+}
+
+/// Returns the path of the state file.
+///
+/// # Example
+/// ```
+/// let file = get_state_file().unwrap();
+/// // -> "/home/me/.local/goup/state.json"
+/// ```
+pub fn get_state_file() -> Result<PathBuf> {
+    get_state_dir().map(|dir| dir.join(STATE_FILE))
+}
+
+/// Returns the current time as Unix timestamp in seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Migrates the state from before schema versioning has been
+/// introduced.
+///
+/// The current version is taken over from the `.current_version`
+/// file and metadata is recorded for every installed SDK, as far
+/// as it can be derived from the installation directory.
+fn migrate_v0(raw: &mut Map<String, Value>) -> Result<()> {
+    if !raw.contains_key("current") {
+        match fs::read_to_string(get_state_dir()?.join(LEGACY_CURRENT_VERSION_FILE)) {
+            Ok(v) => _ = raw.insert("current".into(), v.trim().into()),
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let mut sdks = Map::new();
+    for version in get_installed_versions()? {
+        sdks.insert(
+            version.to_string(),
+            json!(Sdk::from_installation(&version)?),
+        );
+    }
+
+    migrate_last_used(raw, &mut sdks);
+    raw.insert("sdks".into(), sdks.into());
+
+    Ok(())
+}
+
+/// Moves the usage times recorded in `last_used` of a
+/// schema version 0 state into the given SDK metadata.
+fn migrate_last_used(raw: &mut Map<String, Value>, sdks: &mut Map<String, Value>) {
+    let Some(Value::Object(last_used)) = raw.remove("last_used") else {
+        return;
+    };
+
+    for (version, time) in last_used {
+        if let Some(Value::Object(sdk)) = sdks.get_mut(&version) {
+            sdk.insert("last_used".into(), time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_migrate_last_used() {
+        let mut raw = json!({
+            "aliases": {"stable": "1.22"},
+            "last_used": {"1.22.3": 100, "1.21.0": 50}
+        });
+        let mut sdks = json!({
+            "1.22.3": Sdk::new(&"1.22.3".parse().unwrap(), Origin::Downloaded)
+        });
+
+        migrate_last_used(raw.as_object_mut().unwrap(), sdks.as_object_mut().unwrap());
+
+        assert_eq!(json!({"aliases": {"stable": "1.22"}}), raw);
+        assert_eq!(json!(100), sdks["1.22.3"]["last_used"]);
+        assert!(sdks.get("1.21.0").is_none());
+    }
+
+    #[test]
+    fn test_from_raw_current() {
+        let sdk = Sdk::new(&"1.22.3".parse().unwrap(), Origin::Built);
+        let raw = json!({
+            "schema": 1,
+            "current": "1.22.3",
+            "sdks": {"1.22.3": sdk}
+        });
+
+        let (state, migrated) = State::from_raw(raw.as_object().unwrap().clone()).unwrap();
+        assert!(!migrated);
+        assert_eq!(Some("1.22.3"), state.current.as_deref());
+        assert_eq!(Some(&sdk), state.sdk(&"1.22.3".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_from_raw_newer_schema() {
+        let raw = json!({"schema": SCHEMA_VERSION + 1});
+        assert!(State::from_raw(raw.as_object().unwrap().clone()).is_err());
+    }
+}
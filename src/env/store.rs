@@ -0,0 +1,101 @@
+use super::*;
+use crate::{
+    config::{get_config, SYSTEM_STORE},
+    versions::Version,
+};
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Returns the configured location of the system store,
+/// regardless of whether it exists. [`None`] is returned if
+/// the system store has been disabled by configuring an
+/// empty `system_store`.
+///
+/// The system store is a goup home directory managed by
+/// administrators via `goup --system`, whose SDKs can be
+/// selected by all users.
+pub fn get_system_store_path() -> Result<Option<PathBuf>> {
+    match get_config().value(SYSTEM_STORE) {
+        Some(v) if v.trim().is_empty() => Ok(None),
+        Some(v) => Ok(Some(PathBuf::from(v))),
+        None => get_default_system_store_dir().map(Some),
+    }
+}
+
+/// Returns the directory of the system store, if it exists
+/// and is not the current working directory itself.
+///
+/// # Example
+/// ```
+/// let dir = get_system_store_dir().unwrap();
+/// // -> Some("/opt/goup")
+/// ```
+pub fn get_system_store_dir() -> Result<Option<PathBuf>> {
+    let Some(dir) = get_system_store_path()? else {
+        return Ok(None);
+    };
+    let Ok(store) = dir.canonicalize() else {
+        return Ok(None);
+    };
+    if get_work_dir()?.canonicalize().is_ok_and(|dir| dir == store) {
+        return Ok(None);
+    }
+    Ok(Some(dir))
+}
+
+/// Reads the state of the system store, if any.
+pub fn load_system_state() -> Result<Option<State>> {
+    get_system_store_dir()?
+        .map(|dir| State::read_from(&dir))
+        .transpose()
+}
+
+/// Returns the SDK versions provided by the system store.
+///
+/// Only SDKs recorded in the state of the system store are
+/// returned, so SDKs which are still being installed by an
+/// administrator are not visible yet.
+pub fn get_system_versions() -> Result<Vec<Version>> {
+    let (Some(dir), Some(state)) = (get_system_store_dir()?, load_system_state()?) else {
+        return Ok(vec![]);
+    };
+
+    let mut versions: Vec<Version> = state
+        .sdks
+        .keys()
+        .filter(|v| dir.join("installations").join(v).is_dir())
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    versions.sort();
+
+    Ok(versions)
+}
+
+/// Returns whether the SDK of the given [`Version`] is only
+/// provided by the system store and not installed by the user.
+pub fn is_system_version(version: &Version) -> Result<bool> {
+    Ok(!get_installed_versions()?.contains(version) && get_system_versions()?.contains(version))
+}
+
+/// Returns the versions of all SDKs which can be selected,
+/// which are the installed SDKs and those provided by the
+/// system store, sorted and without duplicates.
+pub fn get_available_versions() -> Result<Vec<Version>> {
+    let mut versions = get_installed_versions()?;
+    versions.extend(get_system_versions()?);
+    versions.sort();
+    versions.dedup();
+    Ok(versions)
+}
+
+/// Returns the directory the SDK of the given [`Version`] is
+/// used from. This is its installation directory or, if only
+/// provided by the system store, its directory in the store.
+pub fn get_sdk_dir(version: &Version) -> Result<PathBuf> {
+    if is_system_version(version)? {
+        if let Some(dir) = get_system_store_dir()? {
+            return Ok(dir.join("installations").join(version.to_string()));
+        }
+    }
+    get_version_installation_dir(version)
+}
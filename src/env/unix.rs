@@ -1,13 +1,19 @@
 use super::*;
 use crate::versions::Version;
 use anyhow::Result;
-use std::{fs, os::unix::fs::symlink};
+use std::{
+    fs,
+    os::unix::fs::{symlink, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
 
 /// Creates a symlink to the SDK installation dir of
 /// the given [`Version`].
 ///
 /// If [`Some(Version)`] is passed, the specified SDK
-/// version directory is set.
+/// version directory is set. The symlink is created next
+/// to the current one and renamed over it, so that the
+/// link is swapped atomically.
 /// If [`None`] is passed, the symlink is remoevd, if
 /// existent.
 pub fn link_current_version(v: Option<&Version>) -> Result<()> {
@@ -15,20 +21,140 @@ pub fn link_current_version(v: Option<&Version>) -> Result<()> {
 
     match v {
         None => {
-            if link.exists() {
+            if fs::symlink_metadata(&link).is_ok() {
                 fs::remove_file(&link)?;
             }
         }
         Some(v) => {
-            let original = get_version_installation_dir(v)?;
+            let original = get_sdk_dir(v)?;
+            let tmp_link = get_temp_path(&link);
 
-            if link.exists() {
-                fs::remove_file(&link)?;
+            symlink(original, &tmp_link)?;
+            if let Err(err) = fs::rename(&tmp_link, &link) {
+                _ = fs::remove_file(&tmp_link);
+                return Err(err.into());
             }
+        }
+    }
+
+    Ok(())
+}
 
-            symlink(original, link)?;
+/// Creates a directory symlink at `link` pointing to
+/// the directory `original`.
+pub fn link_dir<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> Result<()> {
+    symlink(original, link)?;
+    Ok(())
+}
+
+/// Creates a symlink at `link` pointing to the same
+/// target as the symlink `original`.
+pub fn copy_link<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> Result<()> {
+    symlink(fs::read_link(original)?, link)?;
+    Ok(())
+}
+
+/// Recursively grants the current user write permissions
+/// on the given path and all of its contents.
+pub fn make_writable_all<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let meta = fs::symlink_metadata(path)?;
+    if meta.is_symlink() {
+        return Ok(());
+    }
+
+    let mut perms = meta.permissions();
+    perms.set_mode(perms.mode() | 0o200);
+    fs::set_permissions(path, perms)?;
+
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            make_writable_all(entry?.path())?;
         }
     }
 
     Ok(())
 }
+
+/// Writes a launcher script to `path` which executes the
+/// `go` binary of the SDK in the given `GOROOT`.
+///
+/// A plain symlink is not sufficient here, because `GOROOT`
+/// is set to the currently selected SDK in the environment,
+/// which would then be used by the launched binary.
+pub fn write_launcher<P: AsRef<Path>, Q: AsRef<Path>>(path: P, goroot: Q) -> Result<()> {
+    let goroot = goroot.as_ref().to_string_lossy();
+    let content = format!(
+        "#!/bin/sh\nGOROOT={} exec {} \"$@\"\n",
+        quote(&goroot),
+        quote(&format!("{goroot}/bin/go"))
+    );
+
+    fs::write(&path, content)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+
+    Ok(())
+}
+
+/// Quotes the given value for `sh` using single quotes, in
+/// which no characters but `'` itself are special.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Returns the system wide configuration directory of goup,
+/// which is managed by administrators.
+///
+/// # Example
+/// ```
+/// let dir = get_system_config_dir().unwrap();
+/// // -> "/etc/goup"
+/// ```
+pub fn get_system_config_dir() -> Result<PathBuf> {
+    Ok(PathBuf::from("/etc/goup"))
+}
+
+/// Returns an identifier of the file described by the given
+/// metadata, which is shared by all hard links to the file.
+pub fn get_file_id(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    Some((meta.dev(), meta.ino()))
+}
+
+/// Returns the default directory of the system store, where
+/// SDKs shared by all users are installed by administrators.
+///
+/// # Example
+/// ```
+/// let dir = get_default_system_store_dir().unwrap();
+/// // -> "/opt/goup"
+/// ```
+pub fn get_default_system_store_dir() -> Result<PathBuf> {
+    Ok(PathBuf::from("/opt/goup"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_launcher() {
+        let tmp = tempfile::tempdir().unwrap();
+        let goroot = tmp.path().join("it's $(a) `b` \"c\"");
+        fs::create_dir_all(goroot.join("bin")).unwrap();
+        fs::write(
+            goroot.join("bin/go"),
+            "#!/bin/sh\nprintf '%s' \"$GOROOT\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(goroot.join("bin/go"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        let launcher = tmp.path().join("go1.22.0");
+        write_launcher(&launcher, &goroot).unwrap();
+
+        let out = std::process::Command::new(&launcher).output().unwrap();
+        assert_eq!(
+            goroot.to_string_lossy(),
+            String::from_utf8_lossy(&out.stdout)
+        );
+    }
+}
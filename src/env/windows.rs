@@ -1,13 +1,20 @@
 use super::*;
 use crate::versions::Version;
 use anyhow::Result;
-use std::{fs, os::windows::fs::symlink_dir};
+use std::{
+    fs,
+    os::windows::fs::{symlink_dir, symlink_file},
+    path::{Path, PathBuf},
+};
 
 /// Creates a symlink to the SDK installation dir of
 /// the given [`Version`].
 ///
 /// If [`Some(Version)`] is passed, the specified SDK
-/// version directory is set.
+/// version directory is set. The symlink is created next
+/// to the current one and renamed in its place. Because
+/// directory symlinks can not be replaced by renaming on
+/// Windows, the old link is removed right before.
 /// If [`None`] is passed, the symlink is remoevd, if
 /// existent.
 pub fn link_current_version(v: Option<&Version>) -> Result<()> {
@@ -15,20 +22,118 @@ pub fn link_current_version(v: Option<&Version>) -> Result<()> {
 
     match v {
         None => {
-            if link.exists() {
+            if fs::symlink_metadata(&link).is_ok() {
                 fs::remove_dir(&link)?;
             }
         }
         Some(v) => {
-            let original = get_version_installation_dir(v)?;
+            let original = get_sdk_dir(v)?;
+            let tmp_link = get_temp_path(&link);
 
-            if link.exists() {
+            symlink_dir(original, &tmp_link)?;
+            if fs::symlink_metadata(&link).is_ok() {
                 fs::remove_dir(&link)?;
             }
+            if let Err(err) = fs::rename(&tmp_link, &link) {
+                _ = fs::remove_dir(&tmp_link);
+                return Err(err.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a directory symlink at `link` pointing to
+/// the directory `original`.
+pub fn link_dir<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> Result<()> {
+    symlink_dir(original, link)?;
+    Ok(())
+}
+
+/// Creates a symlink at `link` pointing to the same
+/// target as the symlink `original`.
+pub fn copy_link<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> Result<()> {
+    let target = fs::read_link(&original)?;
+    match fs::metadata(&original).is_ok_and(|m| m.is_dir()) {
+        true => symlink_dir(target, link)?,
+        false => symlink_file(target, link)?,
+    }
+    Ok(())
+}
+
+/// Recursively removes the read-only attribute from the
+/// given path and all of its contents.
+pub fn make_writable_all<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let meta = fs::symlink_metadata(path)?;
+    if meta.is_symlink() {
+        return Ok(());
+    }
+
+    let mut perms = meta.permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    perms.set_readonly(false);
+    fs::set_permissions(path, perms)?;
 
-            symlink_dir(original, link)?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            make_writable_all(entry?.path())?;
         }
     }
 
     Ok(())
 }
+
+/// Writes a launcher script to `path` which executes the
+/// `go` binary of the SDK in the given `GOROOT`.
+///
+/// A plain symlink is not sufficient here, because `GOROOT`
+/// is set to the currently selected SDK in the environment,
+/// which would then be used by the launched binary.
+pub fn write_launcher<P: AsRef<Path>, Q: AsRef<Path>>(path: P, goroot: Q) -> Result<()> {
+    let goroot = goroot.as_ref().to_string_lossy();
+    let content =
+        format!("@echo off\r\nset \"GOROOT={goroot}\"\r\n\"{goroot}\\bin\\go.exe\" %*\r\n");
+
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".cmd");
+    fs::write(path, content)?;
+
+    Ok(())
+}
+
+/// Returns the system wide configuration directory of goup,
+/// which is managed by administrators.
+///
+/// # Example
+/// ```
+/// let dir = get_system_config_dir().unwrap();
+/// // -> "C:\ProgramData\goup"
+/// ```
+pub fn get_system_config_dir() -> Result<PathBuf> {
+    let program_data = std::env::var("ProgramData")
+        .map_err(|_| anyhow::anyhow!("could not resolve ProgramData directory"))?;
+    Ok(PathBuf::from(program_data).join("goup"))
+}
+
+/// Returns an identifier of the file described by the given
+/// metadata, which is shared by all hard links to the file.
+///
+/// File indexes are not available on Windows, so hard links
+/// can not be detected and [`None`] is returned.
+pub fn get_file_id(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Returns the default directory of the system store, where
+/// SDKs shared by all users are installed by administrators.
+///
+/// # Example
+/// ```
+/// let dir = get_default_system_store_dir().unwrap();
+/// // -> "C:\ProgramData\goup\store"
+/// ```
+pub fn get_default_system_store_dir() -> Result<PathBuf> {
+    get_system_config_dir().map(|dir| dir.join("store"))
+}
@@ -1,7 +1,14 @@
 mod cmd;
 mod commands;
+mod config;
 mod env;
+mod managers;
+mod modcache;
+mod policy;
+mod profile;
 mod progress;
+mod prune;
+mod resolve;
 mod shell;
 mod tui;
 mod util;
@@ -10,7 +17,7 @@ mod versions;
 use crate::shell::ShellEnv;
 use clap::{Parser, Subcommand};
 use commands::*;
-use std::ops::Deref;
+use std::{ops::Deref, path::PathBuf, process};
 
 fn get_long_about() -> String {
     let shell = shell::get_shell();
@@ -30,23 +37,129 @@ fn get_long_about() -> String {
 struct App {
     #[command(subcommand)]
     command: Commands,
+
+    /// Ignore violations of the version policy. Overrides
+    /// are logged in the working directory.
+    #[arg(long, global = true)]
+    ignore_policy: bool,
+
+    /// Directory where SDKs and goup's state are stored.
+    /// Overrides the `GOUP_HOME` environment variable.
+    #[arg(long, global = true, value_name = "DIR")]
+    home: Option<PathBuf>,
+
+    /// Manage the system store shared by all users instead of
+    /// the own SDKs. Usually requires administrator privileges.
+    #[arg(long, global = true, conflicts_with = "home")]
+    system: bool,
 }
 
 register_commands! {
+    Alias
+    Build
     Check
     Clean
+    Config
     Current
+    Dedup
+    Doctor
     Drop
+    Du
     Env
+    History
+    Import
     Ls
     Lsr
+    Pin
+    Prune
+    Reinstall
+    Relocate
+    Toolchains
+    Unpin
+    Update
     Use
+    Verify
 }
 
 fn main() {
     let app = App::parse();
 
-    if let Err(err) = app.command.run() {
+    if let Some(home) = &app.home {
+        std::env::set_var(config::HOME_ENV, home);
+    }
+
+    policy::set_policy_ignored(app.ignore_policy);
+
+    // The configuration command must keep working with invalid
+    // values, so it can be used to repair them. Syntax errors are
+    // reported by the command itself, as they need to be fixed by hand.
+    let init = match &app.command {
+        Commands::Config(_) => Ok(()),
+        _ => config::init_config(),
+    };
+
+    let res = init.and_then(|_| {
+        if let Some(enabled) = config::get_config().colors_enabled() {
+            console::set_colors_enabled(enabled);
+        }
+
+        if app.system {
+            use_system_store()?;
+        }
+
+        let _lock = env::lock_work_dir(app.command.lock_mode()).map_err(|err| {
+            match app.system && is_permission_denied(&err) {
+                true => anyhow::anyhow!(
+                    "The system store can only be managed with administrator privileges."
+                ),
+                false => err,
+            }
+        })?;
+
+        if app.command.lock_mode() != env::LockMode::None {
+            repair_selection();
+        }
+
+        app.command.run()
+    });
+
+    if let Err(err) = res {
+        if let Some(Exit(code)) = err.downcast_ref() {
+            process::exit(*code);
+        }
         error!("{err}");
+        process::exit(1);
+    }
+}
+
+/// Makes the system store the working directory of goup.
+fn use_system_store() -> anyhow::Result<()> {
+    let Some(dir) = env::get_system_store_path()? else {
+        anyhow::bail!(
+            "The system store has been disabled by configuring an empty `{}`.",
+            config::SYSTEM_STORE
+        );
+    };
+    std::env::set_var(config::HOME_ENV, dir);
+    Ok(())
+}
+
+/// Returns whether the given error has been caused by
+/// missing file system permissions.
+fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<std::io::Error>())
+        .any(|e| e.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+/// Repairs the current selection if it disagrees with the link
+/// to the selected SDK and reports the result on stderr.
+fn repair_selection() {
+    match env::repair_current_selection() {
+        Ok(Some(repair)) => tui::eprint_warning(&repair),
+        Ok(None) => {}
+        Err(err) => tui::eprint_warning(&format!(
+            "The selected SDK is inconsistent and could not be repaired: {err}"
+        )),
     }
 }
@@ -0,0 +1,256 @@
+use crate::env::get_home_dir;
+use anyhow::Result;
+use clap::ValueEnum;
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Other Go version managers SDKs can be imported from.
+#[derive(ValueEnum, Clone, Copy)]
+pub enum Manager {
+    /// Go Version Manager (~/.gvm/gos)
+    Gvm,
+    /// goenv (~/.goenv/versions)
+    Goenv,
+    /// asdf with the golang plugin (~/.asdf/installs/golang)
+    Asdf,
+    /// voidint/g (~/.g/versions)
+    G,
+    /// golang.org/dl wrappers (~/sdk)
+    Dl,
+}
+
+/// An SDK installation of another version manager.
+pub struct Installation {
+    /// The name of the installation in the version manager.
+    pub name: String,
+    /// The `GOROOT` directory of the installation.
+    pub goroot: PathBuf,
+}
+
+impl Manager {
+    /// Returns the root directory of the version manager.
+    ///
+    /// Environment variables used by the version manager to
+    /// relocate its root directory are respected.
+    pub fn root(&self) -> Result<PathBuf> {
+        let (var, default) = match self {
+            Self::Gvm => (Some("GVM_ROOT"), ".gvm"),
+            Self::Goenv => (Some("GOENV_ROOT"), ".goenv"),
+            Self::Asdf => (Some("ASDF_DATA_DIR"), ".asdf"),
+            Self::G => (Some("G_HOME"), ".g"),
+            Self::Dl => (None, "sdk"),
+        };
+
+        if let Some(root) = var.and_then(env::var_os) {
+            return Ok(root.into());
+        }
+
+        get_home_dir().map(|home| home.join(default))
+    }
+
+    /// Returns the directory containing all SDK installations
+    /// of the version manager with the given root directory.
+    fn installations_dir(&self, root: &Path) -> PathBuf {
+        match self {
+            Self::Gvm => root.join("gos"),
+            Self::Goenv | Self::G => root.join("versions"),
+            Self::Asdf => root.join("installs").join("golang"),
+            Self::Dl => root.to_path_buf(),
+        }
+    }
+
+    /// Returns all SDK installations of the version manager.
+    ///
+    /// If the version manager is not installed, an empty list
+    /// is returned.
+    pub fn discover(&self) -> Result<Vec<Installation>> {
+        self.discover_in(&self.root()?)
+    }
+
+    fn discover_in(&self, root: &Path) -> Result<Vec<Installation>> {
+        let dir = match self.installations_dir(root).read_dir() {
+            Ok(v) => v,
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut installations = vec![];
+        for entry in dir {
+            let entry = entry?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let goroot = match self {
+                // golang.org/dl also stores its download
+                // archives and gotip in the sdk directory.
+                Self::Dl if !name.starts_with("go1") => continue,
+                Self::Asdf => entry.path().join("go"),
+                Self::G if entry.path().join("go").is_dir() => entry.path().join("go"),
+                _ => entry.path(),
+            };
+
+            installations.push(Installation { name, goroot });
+        }
+
+        installations.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(installations)
+    }
+
+    /// Returns the name of the installation currently selected
+    /// in the version manager, if any.
+    pub fn current(&self) -> Result<Option<String>> {
+        self.current_in(&self.root()?, &get_home_dir()?)
+    }
+
+    fn current_in(&self, root: &Path, home: &Path) -> Result<Option<String>> {
+        let current = match self {
+            Self::Gvm => read_optional(root.join("environments").join("default"))?.and_then(|s| {
+                s.lines()
+                    .filter(|l| l.contains("GOROOT="))
+                    .find_map(|l| l.split_once("gos/"))
+                    .map(|(_, v)| v.trim_end_matches(['"', '\'']).to_string())
+            }),
+            Self::Goenv => read_optional(root.join("version"))?
+                .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+                .filter(|v| v != "system"),
+            Self::Asdf => {
+                let file = env::var("ASDF_DEFAULT_TOOL_VERSIONS_FILENAME")
+                    .unwrap_or(".tool-versions".into());
+                read_optional(home.join(file))?.and_then(|s| {
+                    s.lines()
+                        .filter_map(|l| l.trim().strip_prefix("golang "))
+                        .find_map(|v| v.split_whitespace().next().map(String::from))
+                })
+            }
+            Self::G => fs::read_link(root.join("go")).ok().and_then(|target| {
+                target
+                    .components()
+                    .rev()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .find(|c| c != "go")
+            }),
+            Self::Dl => None,
+        };
+
+        Ok(current.filter(|c| !c.is_empty()))
+    }
+}
+
+impl fmt::Display for Manager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Gvm => "gvm",
+            Self::Goenv => "goenv",
+            Self::Asdf => "asdf",
+            Self::G => "g",
+            Self::Dl => "golang.org/dl",
+        };
+        f.write_str(name)
+    }
+}
+
+fn read_optional<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(v) => Ok(Some(v)),
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mkdir(path: PathBuf) {
+        fs::create_dir_all(path).unwrap();
+    }
+
+    fn names(installations: &[Installation]) -> Vec<&str> {
+        installations.iter().map(|i| i.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_discover() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        mkdir(root.join("gos/go1.21.0/bin"));
+        mkdir(root.join("gos/go1.22.1/bin"));
+        fs::write(root.join("gos/README"), "").unwrap();
+        let found = Manager::Gvm.discover_in(root).unwrap();
+        assert_eq!(vec!["go1.21.0", "go1.22.1"], names(&found));
+        assert_eq!(root.join("gos/go1.21.0"), found[0].goroot);
+
+        mkdir(root.join("installs/golang/1.22.1/go"));
+        let found = Manager::Asdf.discover_in(root).unwrap();
+        assert_eq!(root.join("installs/golang/1.22.1/go"), found[0].goroot);
+
+        mkdir(root.join("versions/1.21.0/go"));
+        mkdir(root.join("versions/1.22.1"));
+        let found = Manager::G.discover_in(root).unwrap();
+        assert_eq!(root.join("versions/1.21.0/go"), found[0].goroot);
+        assert_eq!(root.join("versions/1.22.1"), found[1].goroot);
+
+        let dl = root.join("sdk");
+        mkdir(dl.join("go1.22.1"));
+        mkdir(dl.join("gotip"));
+        let found = Manager::Dl.discover_in(&dl).unwrap();
+        assert_eq!(vec!["go1.22.1"], names(&found));
+
+        let missing = root.join("missing");
+        assert!(Manager::Goenv.discover_in(&missing).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_current() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (root, home) = (tmp.path().join("root"), tmp.path().join("home"));
+        mkdir(root.join("environments"));
+        mkdir(home.clone());
+
+        assert_eq!(None, Manager::Gvm.current_in(&root, &home).unwrap());
+
+        fs::write(
+            root.join("environments/default"),
+            "export GVM_ROOT; GVM_ROOT=\"/home/me/.gvm\"\n\
+            export GOROOT; GOROOT=\"$GVM_ROOT/gos/go1.22.1\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Some("go1.22.1".into()),
+            Manager::Gvm.current_in(&root, &home).unwrap()
+        );
+
+        fs::write(root.join("version"), "1.21.0\n").unwrap();
+        assert_eq!(
+            Some("1.21.0".into()),
+            Manager::Goenv.current_in(&root, &home).unwrap()
+        );
+        fs::write(root.join("version"), "system\n").unwrap();
+        assert_eq!(None, Manager::Goenv.current_in(&root, &home).unwrap());
+
+        fs::write(
+            home.join(".tool-versions"),
+            "nodejs 20.0.0\ngolang 1.22.1 1.21.0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Some("1.22.1".into()),
+            Manager::Asdf.current_in(&root, &home).unwrap()
+        );
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("versions/1.21.0/go"), root.join("go")).unwrap();
+            assert_eq!(
+                Some("1.21.0".into()),
+                Manager::G.current_in(&root, &home).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    cmd::exec,
+    env::{download::*, get_home_dir, make_writable_all},
+    versions::Version,
+};
+use anyhow::Result;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Prefix of toolchain module directories in the
+/// module cache.
+const TOOLCHAIN_MODULE_PREFIX: &str = "toolchain@v0.0.1-go";
+
+/// A Go toolchain which has been downloaded into the
+/// module cache by the Go command (`GOTOOLCHAIN=auto`).
+pub struct Toolchain {
+    /// The version of the toolchain.
+    pub version: Version,
+    /// The platform of the toolchain (like `linux-amd64`).
+    pub platform: String,
+    /// The `GOROOT` directory of the toolchain in the
+    /// module cache.
+    pub dir: PathBuf,
+}
+
+impl Toolchain {
+    /// Returns true if the toolchain has been built
+    /// for the current platform.
+    pub fn is_native(&self) -> bool {
+        self.platform == format!("{GOOS}-{GOARCH}")
+    }
+
+    /// Removes the toolchain from the module cache, including
+    /// the downloaded module files.
+    ///
+    /// Because files in the module cache are read-only, write
+    /// permissions are granted before removal.
+    pub fn remove(&self, modcache: &Path) -> Result<()> {
+        make_writable_all(&self.dir)?;
+        fs::remove_dir_all(&self.dir)?;
+
+        let download_dir = modcache
+            .join("cache")
+            .join("download")
+            .join("golang.org")
+            .join("toolchain")
+            .join("@v");
+        let file_prefix = format!("v0.0.1-go{}.{}.", self.version, self.platform);
+
+        let entries = match download_dir.read_dir() {
+            Ok(v) => v,
+            Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(&file_prefix)
+            {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the location of the Go module cache.
+///
+/// The location is taken from the `GOMODCACHE` environment
+/// variable. If not set, it is queried using `go env GOMODCACHE`.
+/// If this fails as well, the default location inside of the
+/// `GOPATH` is returned.
+pub fn get_modcache_dir() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("GOMODCACHE").filter(|v| !v.is_empty()) {
+        return Ok(dir.into());
+    }
+
+    if let Ok(dir) = exec(&["go", "env", "GOMODCACHE"]) {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Ok(dir.into());
+        }
+    }
+
+    let gopath = match env::var_os("GOPATH").filter(|v| !v.is_empty()) {
+        Some(v) => env::split_paths(&v).next().unwrap_or_default(),
+        None => get_home_dir()?.join("go"),
+    };
+
+    Ok(gopath.join("pkg").join("mod"))
+}
+
+/// Returns all toolchains found in the given module cache
+/// directory, sorted by version.
+pub fn get_toolchains(modcache: &Path) -> Result<Vec<Toolchain>> {
+    let dir = match modcache.join("golang.org").read_dir() {
+        Ok(v) => v,
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound) => return Ok(vec![]),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut toolchains = vec![];
+    for entry in dir {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        let Some((version, platform)) = parse_toolchain_dir_name(&name) else {
+            continue;
+        };
+
+        toolchains.push(Toolchain {
+            version,
+            platform,
+            dir: entry.path(),
+        });
+    }
+
+    toolchains.sort_by(|a, b| a.version.cmp(&b.version));
+
+    Ok(toolchains)
+}
+
+/// Parses a toolchain module directory name like
+/// `toolchain@v0.0.1-go1.22.3.linux-amd64` into the
+/// contained [`Version`] and platform.
+fn parse_toolchain_dir_name(name: &str) -> Option<(Version, String)> {
+    let (version, platform) = name
+        .strip_prefix(TOOLCHAIN_MODULE_PREFIX)?
+        .rsplit_once('.')?;
+
+    if !platform.contains('-') {
+        return None;
+    }
+
+    let version: Version = version.parse().ok()?;
+    if version.is_ident() {
+        return None;
+    }
+
+    Some((version, platform.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_toolchain_dir_name() {
+        assert_eq!(
+            Some(("1.22.3".parse().unwrap(), "linux-amd64".into())),
+            parse_toolchain_dir_name("toolchain@v0.0.1-go1.22.3.linux-amd64")
+        );
+        assert_eq!(
+            Some(("1.21rc2".parse().unwrap(), "darwin-arm64".into())),
+            parse_toolchain_dir_name("toolchain@v0.0.1-go1.21rc2.darwin-arm64")
+        );
+        assert_eq!(None, parse_toolchain_dir_name("x@v0.3.0"));
+        assert_eq!(None, parse_toolchain_dir_name("toolchain@v0.0.1-go1.22.3"));
+    }
+}
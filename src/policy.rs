@@ -0,0 +1,414 @@
+use crate::{
+    config::{self, get_config},
+    env::*,
+    versions::{Version, VersionPart},
+    warning,
+};
+use anyhow::Result;
+use serde::Deserialize;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+const POLICY_FILE: &str = "policy.toml";
+const OVERRIDE_LOG_FILE: &str = "policy_overrides.log";
+
+static POLICY: OnceLock<Policy> = OnceLock::new();
+static IGNORED: AtomicBool = AtomicBool::new(false);
+
+/// A version policy which restricts which SDK versions
+/// are allowed to be installed and selected.
+///
+/// The policy is read from the system wide configuration
+/// directory *(see [`get_system_config_dir`])*, which is managed
+/// by administrators. A policy file configured as `policy` can
+/// only tighten it *(see [`Policy::tighten`])*.
+///
+/// # Example
+/// ```toml
+/// min_version = "1.21"
+/// banned = ["1.22.0", "1.21.2"]
+/// allow_prerelease = false
+/// max_patches_behind = 2
+/// ```
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// The minimum allowed version.
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub min_version: Option<Version>,
+
+    /// Versions which are not allowed.
+    #[serde(default, deserialize_with = "deserialize_versions")]
+    pub banned: Vec<Version>,
+
+    /// Whether pre-releases are allowed.
+    #[serde(default = "default_true")]
+    pub allow_prerelease: bool,
+
+    /// The maximum number of patch releases a version may
+    /// be behind the latest patch of its minor version.
+    #[serde(default)]
+    pub max_patches_behind: Option<usize>,
+
+    /// Source files of the policy.
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
+
+    /// Whether violations are ignored and logged instead.
+    #[serde(skip)]
+    pub ignored: bool,
+}
+
+/// A violation of a [`Policy`].
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Violation {
+    #[error("version {0} is below the minimum allowed version {1}")]
+    BelowMinimum(Version, Version),
+    #[error("version {0} is banned")]
+    Banned(Version),
+    #[error("version {0} is a pre-release, which are not allowed")]
+    Prerelease(Version),
+    #[error("version {0} is {1} patch releases behind {2}, but at most {3} are allowed")]
+    PatchesBehind(Version, usize, Version, usize),
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn deserialize_version<'de, D>(d: D) -> Result<Option<Version>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let v: Option<String> = Deserialize::deserialize(d)?;
+    v.map(|v| v.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_versions<'de, D>(d: D) -> Result<Vec<Version>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let v: Vec<String> = Deserialize::deserialize(d)?;
+    v.iter()
+        .map(|v| v.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            min_version: None,
+            banned: vec![],
+            allow_prerelease: true,
+            max_patches_behind: None,
+            sources: vec![],
+            ignored: false,
+        }
+    }
+}
+
+impl Policy {
+    /// Reads the system policy and tightens it with the policy
+    /// file configured as `policy`, if any. If no policy file
+    /// exists, the default policy allowing all versions is returned.
+    pub fn load() -> Result<Self> {
+        let mut policy = Self::read(&get_system_config_dir()?.join(POLICY_FILE), false)?;
+
+        if let Some(path) = get_config().value(config::POLICY) {
+            policy.tighten(Self::read(&PathBuf::from(path), true)?);
+        }
+
+        Ok(policy)
+    }
+
+    fn read(path: &PathBuf, required: bool) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(err) if !required && matches!(err.kind(), io::ErrorKind::NotFound) => {
+                return Ok(Self::default())
+            }
+            Err(err) => anyhow::bail!("failed reading policy file {}: {err}", path.display()),
+        };
+
+        let mut policy: Self = toml::from_str(&content)
+            .map_err(|err| anyhow::anyhow!("invalid policy file {}: {err}", path.display()))?;
+        policy.sources = vec![path.clone()];
+
+        Ok(policy)
+    }
+
+    /// Combines this policy with the given one, so that only
+    /// versions permitted by both policies are permitted.
+    pub fn tighten(&mut self, other: Policy) {
+        self.min_version = self.min_version.take().max(other.min_version);
+        self.banned.extend(other.banned);
+        self.allow_prerelease &= other.allow_prerelease;
+        self.max_patches_behind = match (self.max_patches_behind, other.max_patches_behind) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.sources.extend(other.sources);
+    }
+
+    /// Returns the first violation of the policy by the given
+    /// version, if any. The given upstream versions *(sorted from
+    /// oldest to latest)* are used to determine how many patch
+    /// releases the version is behind.
+    ///
+    /// SDKs which are no upstream releases are not restricted.
+    pub fn check(&self, version: &Version, upstream_versions: &[Version]) -> Option<Violation> {
+        if version.is_ident() {
+            return None;
+        }
+
+        if self.banned.contains(version) {
+            return Some(Violation::Banned(version.clone()));
+        }
+
+        if let Some(min) = &self.min_version {
+            if version < min {
+                return Some(Violation::BelowMinimum(version.clone(), min.clone()));
+            }
+        }
+
+        if !self.allow_prerelease && !version.is_stable() {
+            return Some(Violation::Prerelease(version.clone()));
+        }
+
+        if let Some(max) = self.max_patches_behind {
+            let minor = version.strip_after(VersionPart::Minor);
+            let newer: Vec<_> = upstream_versions
+                .iter()
+                .filter(|v| v.is_stable() && minor.covers(v) && *v > version)
+                .collect();
+            if newer.len() > max {
+                let latest = newer.iter().max().map(|v| (*v).clone()).unwrap_or_default();
+                return Some(Violation::PatchesBehind(
+                    version.clone(),
+                    newer.len(),
+                    latest,
+                    max,
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Returns true if the given version does not violate the policy.
+    pub fn permits(&self, version: &Version, upstream_versions: &[Version]) -> bool {
+        self.check(version, upstream_versions).is_none()
+    }
+
+    /// Checks the given version against the policy and returns an
+    /// error describing the violation.
+    ///
+    /// If the policy is ignored, the violation is printed as warning
+    /// and logged to the override log file instead.
+    pub fn enforce(&self, version: &Version, upstream_versions: &[Version]) -> Result<()> {
+        let Some(violation) = self.check(version, upstream_versions) else {
+            return Ok(());
+        };
+
+        if self.ignored {
+            warning!("Ignoring policy violation: {violation}.");
+            log_override(&violation)?;
+            return Ok(());
+        }
+
+        let sources: Vec<_> = self
+            .sources
+            .iter()
+            .map(|s| s.display().to_string())
+            .collect();
+        let source = match sources.is_empty() {
+            true => String::new(),
+            false => format!(" (see {})", sources.join(", ")),
+        };
+        anyhow::bail!(
+            "Policy violation: {violation}{source}.\n\
+            Use `--ignore-policy` to override the policy. Overrides are logged."
+        )
+    }
+
+    /// Returns the first of the given candidate versions which is
+    /// permitted by the policy.
+    ///
+    /// If no candidate is permitted, the first candidate is enforced
+    /// *(see [`Policy::enforce`])*, which either results in an error
+    /// describing the violation or, if the policy is ignored, in
+    /// returning the first candidate.
+    pub fn select<'a, I>(
+        &self,
+        candidates: I,
+        upstream_versions: &[Version],
+    ) -> Result<Option<&'a Version>>
+    where
+        I: IntoIterator<Item = &'a Version>,
+    {
+        let candidates: Vec<_> = candidates.into_iter().collect();
+
+        if !self.ignored {
+            if let Some(v) = candidates
+                .iter()
+                .find(|v| self.permits(v, upstream_versions))
+            {
+                return Ok(Some(v));
+            }
+        }
+
+        match candidates.first() {
+            Some(v) => self.enforce(v, upstream_versions).map(|_| Some(*v)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Sets whether violations of the global policy *(see
+/// [`get_policy`])* are not enforced but logged.
+pub fn set_policy_ignored(ignored: bool) {
+    IGNORED.store(ignored, Ordering::Relaxed);
+}
+
+/// Returns the global policy, which is loaded from the policy
+/// file when it is first used. Commands which do not resolve
+/// versions therefore keep working with a broken policy file.
+pub fn get_policy() -> Result<&'static Policy> {
+    if let Some(policy) = POLICY.get() {
+        return Ok(policy);
+    }
+
+    let mut policy = Policy::load()?;
+    policy.ignored = IGNORED.load(Ordering::Relaxed);
+    Ok(POLICY.get_or_init(|| policy))
+}
+
+/// Returns the path of the file where policy overrides are logged.
+pub fn get_override_log_file() -> Result<PathBuf> {
+    get_state_dir().map(|dir| dir.join(OVERRIDE_LOG_FILE))
+}
+
+fn log_override(violation: &Violation) -> Result<()> {
+    ensure_dir(get_state_dir()?)?;
+
+    let user = env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default();
+    let args: Vec<_> = env::args().collect();
+
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_override_log_file()?)?;
+    writeln!(f, "{}\t{user}\t{violation}\t{}", now(), args.join(" "))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_versions() -> Vec<Version> {
+        vec![
+            "1.21.0".parse().unwrap(),
+            "1.21.1".parse().unwrap(),
+            "1.21.2".parse().unwrap(),
+            "1.21.3".parse().unwrap(),
+            "1.22rc1".parse().unwrap(),
+            "1.22.0".parse().unwrap(),
+        ]
+    }
+
+    fn get_policy() -> Policy {
+        toml::from_str(
+            r#"
+            min_version = "1.21.1"
+            banned = ["1.21.2"]
+            allow_prerelease = false
+            max_patches_behind = 1
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn check() {
+        let versions = get_versions();
+        let policy = get_policy();
+        let v = |s: &str| -> Version { s.parse().unwrap() };
+
+        assert_eq!(
+            Some(Violation::BelowMinimum(v("1.21.0"), v("1.21.1"))),
+            policy.check(&v("1.21.0"), &versions)
+        );
+        assert_eq!(
+            Some(Violation::Banned(v("1.21.2"))),
+            policy.check(&v("1.21.2"), &versions)
+        );
+        assert_eq!(
+            Some(Violation::Prerelease(v("1.22rc1"))),
+            policy.check(&v("1.22rc1"), &versions)
+        );
+        assert_eq!(
+            Some(Violation::PatchesBehind(v("1.21.1"), 2, v("1.21.3"), 1)),
+            policy.check(&v("1.21.1"), &versions)
+        );
+        assert_eq!(None, policy.check(&v("1.21.3"), &versions));
+        assert_eq!(None, policy.check(&Version::ident("tip"), &versions));
+
+        assert!(Policy::default().permits(&v("1.21.0"), &versions));
+    }
+
+    #[test]
+    fn select() {
+        let versions = get_versions();
+        let policy = get_policy();
+
+        let selected = policy.select(versions.iter().rev(), &versions).unwrap();
+        assert_eq!(Some(&versions[5]), selected);
+
+        let selected = policy.select(versions[..3].iter().rev(), &versions);
+        assert!(selected.is_err());
+    }
+
+    #[test]
+    fn tighten() {
+        let versions = get_versions();
+        let v = |s: &str| -> Version { s.parse().unwrap() };
+
+        let mut policy = get_policy();
+        policy.tighten(Policy::default());
+        assert_eq!(get_policy(), policy);
+
+        let mut policy = Policy::default();
+        policy.tighten(get_policy());
+        assert_eq!(get_policy(), policy);
+
+        let mut policy = get_policy();
+        policy.tighten(
+            toml::from_str(
+                r#"
+                min_version = "1.21.0"
+                banned = ["1.21.3"]
+                max_patches_behind = 0
+                "#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(Some(v("1.21.1")), policy.min_version);
+        assert!(!policy.allow_prerelease);
+        assert_eq!(Some(0), policy.max_patches_behind);
+        assert!(!policy.permits(&v("1.21.3"), &versions));
+        assert!(policy.permits(&v("1.22.0"), &versions));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::config::{get_config, INSTALL_EXCLUDE, INSTALL_INCLUDE, INSTALL_PROFILE};
+use anyhow::Result;
+use glob::{MatchOptions, Pattern};
+use std::path::{Path, PathBuf};
+
+/// The profile installing all files of an SDK.
+pub const FULL: &str = "full";
+
+/// The profile skipping tests, documentation and test data.
+pub const MINIMAL: &str = "minimal";
+
+/// The profile using the configured `install.include`
+/// and `install.exclude` patterns.
+pub const CUSTOM: &str = "custom";
+
+/// Paths skipped by the minimal profile, which are only
+/// needed to test the toolchain itself or to read docs.
+const MINIMAL_EXCLUDES: &[&str] = &["go/test", "go/doc", "go/api", "go/misc", "**/testdata"];
+
+/// Defines which files of an SDK archive are unpacked
+/// when installing it.
+///
+/// Patterns are matched against the path of each archive entry
+/// *(like `go/src/fmt/print.go`)* and its parent directories, so
+/// excluding a directory skips all of its contents. Included
+/// paths take precedence over excluded ones.
+#[derive(Clone, Debug)]
+pub struct InstallProfile {
+    pub name: String,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl InstallProfile {
+    /// Creates the profile with the given name. Custom profiles
+    /// use the patterns configured as `install.include` and
+    /// `install.exclude`.
+    pub fn new(name: &str) -> Result<Self> {
+        let (include, exclude) = match name {
+            FULL => (vec![], vec![]),
+            MINIMAL => (vec![], parse_patterns(&MINIMAL_EXCLUDES.join(","))?),
+            CUSTOM => {
+                let config = get_config();
+                let patterns = |key| {
+                    parse_patterns(&config.value(key).unwrap_or_default())
+                        .map_err(|err| anyhow::anyhow!("invalid value for `{key}`: {err}"))
+                };
+                (patterns(INSTALL_INCLUDE)?, patterns(INSTALL_EXCLUDE)?)
+            }
+            _ => anyhow::bail!(
+                "unknown install profile `{name}`, must be one of `{FULL}`, `{MINIMAL}` or `{CUSTOM}`"
+            ),
+        };
+
+        Ok(Self {
+            name: name.into(),
+            include,
+            exclude,
+        })
+    }
+
+    /// Creates the profile with the given name from previously
+    /// resolved patterns, like those recorded for an installed SDK.
+    pub fn with_patterns(name: &str, include: &[String], exclude: &[String]) -> Result<Self> {
+        let parse = |patterns: &[String]| -> Result<Vec<Pattern>> {
+            patterns
+                .iter()
+                .map(|p| Pattern::new(p).map_err(Into::into))
+                .collect()
+        };
+
+        Ok(Self {
+            name: name.into(),
+            include: parse(include)?,
+            exclude: parse(exclude)?,
+        })
+    }
+
+    /// Returns the patterns of included paths.
+    pub fn include_patterns(&self) -> Vec<String> {
+        self.include.iter().map(|p| p.as_str().into()).collect()
+    }
+
+    /// Returns the patterns of excluded paths.
+    pub fn exclude_patterns(&self) -> Vec<String> {
+        self.exclude.iter().map(|p| p.as_str().into()).collect()
+    }
+
+    /// Returns whether all files are installed.
+    pub fn is_full(&self) -> bool {
+        self.exclude.is_empty()
+    }
+
+    /// Returns whether the archive entry with the given
+    /// path should be unpacked.
+    pub fn includes(&self, path: &Path) -> bool {
+        // Normalizes directory entries like `go/doc/`.
+        let path: PathBuf = path.components().collect();
+        let matches = |patterns: &[Pattern]| {
+            path.ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| {
+                    patterns
+                        .iter()
+                        .any(|pat| pat.matches_path_with(p, MATCH_OPTIONS))
+                })
+        };
+
+        matches(&self.include) || !matches(&self.exclude)
+    }
+}
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Parses a comma separated list of glob patterns.
+pub fn parse_patterns(spec: &str) -> Result<Vec<Pattern>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| Pattern::new(p.trim_end_matches('/')).map_err(Into::into))
+        .collect()
+}
+
+/// Returns the install profile passed by the user or, if
+/// not passed, the one configured as `install.profile`.
+pub fn get_install_profile(name: Option<&str>) -> Result<InstallProfile> {
+    match name {
+        Some(name) => InstallProfile::new(name),
+        None => InstallProfile::new(
+            &get_config()
+                .value(INSTALL_PROFILE)
+                .unwrap_or_else(|| FULL.into()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_minimal_profile() {
+        let profile = InstallProfile::new(MINIMAL).unwrap();
+        let includes = |p: &str| profile.includes(Path::new(p));
+
+        assert!(includes("go"));
+        assert!(includes("go/bin/go"));
+        assert!(includes("go/src/fmt/print.go"));
+        assert!(includes("go/testing"));
+        assert!(!includes("go/test"));
+        assert!(!includes("go/test/"));
+        assert!(!includes("go/test/fixedbugs/issue1.go"));
+        assert!(!includes("go/doc/go_spec.html"));
+        assert!(!includes("go/src/fmt/testdata/a.txt"));
+        assert!(!includes("go/src/cmd/go/testdata"));
+    }
+
+    #[test]
+    fn test_include_precedence() {
+        let profile = InstallProfile {
+            name: CUSTOM.into(),
+            include: parse_patterns("go/misc/wasm").unwrap(),
+            exclude: parse_patterns("go/misc/, go/*/*.html").unwrap(),
+        };
+        let includes = |p: &str| profile.includes(Path::new(p));
+
+        assert!(includes("go/misc/wasm/wasm_exec.js"));
+        assert!(!includes("go/misc/cgo/a.go"));
+        assert!(!includes("go/doc/go_spec.html"));
+        assert!(includes("go/doc/gopher/a.png"));
+        assert!(InstallProfile::new(FULL).unwrap().is_full());
+
+        let recorded = InstallProfile::with_patterns(
+            CUSTOM,
+            &profile.include_patterns(),
+            &profile.exclude_patterns(),
+        )
+        .unwrap();
+        assert_eq!(vec!["go/misc", "go/*/*.html"], recorded.exclude_patterns());
+        assert!(recorded.includes(Path::new("go/misc/wasm/wasm_exec.js")));
+        assert!(InstallProfile::new("slim").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    config::{get_config, PRUNE_AUTO},
+    env::*,
+    versions::{Version, VersionPart},
+};
+use anyhow::Result;
+use std::{collections::BTreeSet, fmt, fs, str::FromStr, time::UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// A set of retention rules which define which installed
+/// SDKs are removed when pruning.
+///
+/// A policy can be parsed from a comma separated list of
+/// rules, like `keep-latest-patch,keep-minors=3`.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// Keep only the latest patch release of each minor version.
+    pub keep_latest_patch: bool,
+    /// Keep only SDKs of the given number of latest minor versions.
+    pub keep_minors: Option<usize>,
+    /// Remove pre-releases when a stable release of the same
+    /// minor version is installed.
+    pub drop_superseded_pre: bool,
+    /// Remove SDKs which have not been used for the given
+    /// number of days.
+    pub unused_days: Option<u64>,
+}
+
+/// An installed SDK which is considered by a [`PrunePolicy`].
+pub struct PruneCandidate<'a> {
+    pub version: &'a Version,
+    /// Unix timestamp of when the SDK has been used last.
+    pub last_used: Option<u64>,
+}
+
+impl PrunePolicy {
+    /// Returns true if the policy contains no rules.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns all versions of the given `candidates` which should
+    /// be removed according to the policy paired with the reason
+    /// for removal, sorted by version.
+    ///
+    /// Versions contained in `protected` as well as SDKs which are
+    /// no upstream releases are never removed.
+    pub fn apply<'a>(
+        &self,
+        candidates: &[PruneCandidate<'a>],
+        protected: &[Version],
+        now: u64,
+    ) -> Vec<(&'a Version, String)> {
+        let releases: Vec<_> = candidates
+            .iter()
+            .filter(|c| !c.version.is_ident())
+            .collect();
+
+        let minors: BTreeSet<_> = releases
+            .iter()
+            .map(|c| c.version.strip_after(VersionPart::Minor))
+            .collect();
+
+        let mut res = vec![];
+        for c in &releases {
+            let v = c.version;
+            if protected.contains(v) {
+                continue;
+            }
+
+            let minor = v.strip_after(VersionPart::Minor);
+            let in_minor = || {
+                releases
+                    .iter()
+                    .map(|c| c.version)
+                    .filter(|o| minor.covers(o))
+            };
+
+            let reason = if self.keep_latest_patch
+                && v.is_stable()
+                && in_minor().any(|o| o.is_stable() && o > v)
+            {
+                Some("superseded by a newer patch release".to_string())
+            } else if self
+                .keep_minors
+                .is_some_and(|n| minors.iter().rev().take(n).all(|m| m != &minor))
+            {
+                Some(format!(
+                    "not within the {} latest minor versions",
+                    self.keep_minors.unwrap_or_default()
+                ))
+            } else if self.drop_superseded_pre
+                && !v.is_stable()
+                && in_minor().any(|o| o.is_stable())
+            {
+                Some("superseded by a stable release".to_string())
+            } else if let Some(days) = self.unused_days {
+                c.last_used
+                    .filter(|t| now.saturating_sub(*t) > days * SECONDS_PER_DAY)
+                    .map(|t| format!("unused for {} days", (now - t) / SECONDS_PER_DAY))
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                res.push((v, reason));
+            }
+        }
+
+        res.sort_by(|a, b| a.0.cmp(b.0));
+        res
+    }
+}
+
+/// Returns the policy which is applied automatically after
+/// installing an SDK, if configured as `prune.auto`.
+pub fn get_auto_prune_policy() -> Result<Option<PrunePolicy>> {
+    let Some(spec) = get_config().value(PRUNE_AUTO) else {
+        return Ok(None);
+    };
+
+    let policy: PrunePolicy = spec
+        .parse()
+        .map_err(|err| anyhow::anyhow!("invalid value for `{PRUNE_AUTO}`: {err}"))?;
+
+    Ok(Some(policy).filter(|p| !p.is_empty()))
+}
+
+/// Applies the given policy to all installed SDKs and removes
+/// the selected SDKs, unless `dry_run` is set. The currently
+/// selected SDK and pinned SDKs are never removed.
+///
+/// Returns the selected versions paired with the reason
+/// for removal.
+pub fn prune_installed(policy: &PrunePolicy, dry_run: bool) -> Result<Vec<(Version, String)>> {
+    let installed = get_installed_versions()?;
+    let state = State::load()?;
+
+    let candidates = installed
+        .iter()
+        .map(|v| {
+            Ok(PruneCandidate {
+                version: v,
+                // SDKs which have never been selected fall back
+                // to their install time.
+                last_used: match state.sdk(v) {
+                    Some(sdk) => Some(sdk.last_used.unwrap_or(sdk.installed_at)),
+                    None => fs::metadata(get_version_installation_dir(v)?)?
+                        .modified()?
+                        .duration_since(UNIX_EPOCH)
+                        .ok()
+                        .map(|d| d.as_secs()),
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut protected = get_pinned_versions()?;
+    protected.extend(get_current_version()?);
+
+    let selected: Vec<_> = policy
+        .apply(&candidates, &protected, now())
+        .into_iter()
+        .map(|(v, reason)| (v.clone(), reason))
+        .collect();
+
+    if dry_run {
+        return Ok(selected);
+    }
+
+    let mut pruned = vec![];
+    for (v, reason) in selected {
+        // SDKs which are currently being installed by
+        // another goup process are kept.
+        let Some(_lock) = try_lock_install(&v)? else {
+            continue;
+        };
+        drop_version(&v)?;
+        pruned.push((v, reason));
+    }
+
+    if !pruned.is_empty() {
+        sync_versioned_binaries()?;
+    }
+
+    Ok(pruned)
+}
+
+impl FromStr for PrunePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policy = Self::default();
+
+        for rule in s.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let (key, value) = match rule.split_once('=') {
+                Some((k, v)) => (k.trim(), Some(v.trim())),
+                None => (rule, None),
+            };
+
+            let number = || -> Result<u64> {
+                value
+                    .ok_or_else(|| anyhow::anyhow!("rule `{key}` requires a value"))?
+                    .parse()
+                    .map_err(|err| anyhow::anyhow!("invalid value for rule `{key}`: {err}"))
+            };
+
+            match key {
+                "keep-latest-patch" => policy.keep_latest_patch = true,
+                "keep-minors" => policy.keep_minors = Some(number()? as usize),
+                "drop-superseded-pre" => policy.drop_superseded_pre = true,
+                "unused-days" => policy.unused_days = Some(number()?),
+                _ => anyhow::bail!("unknown retention rule `{key}`"),
+            }
+        }
+
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for PrunePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rules = vec![];
+
+        if self.keep_latest_patch {
+            rules.push("keep-latest-patch".to_string());
+        }
+        if let Some(n) = self.keep_minors {
+            rules.push(format!("keep-minors={n}"));
+        }
+        if self.drop_superseded_pre {
+            rules.push("drop-superseded-pre".to_string());
+        }
+        if let Some(n) = self.unused_days {
+            rules.push(format!("unused-days={n}"));
+        }
+
+        f.write_str(&rules.join(","))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_versions() -> Vec<Version> {
+        vec![
+            "1.20.4".parse().unwrap(),
+            "1.21.0".parse().unwrap(),
+            "1.21.12".parse().unwrap(),
+            "1.21.13".parse().unwrap(),
+            "1.22rc1".parse().unwrap(),
+            "1.22.0".parse().unwrap(),
+            "1.22.1".parse().unwrap(),
+            "1.23rc2".parse().unwrap(),
+            "tip".parse().unwrap(),
+        ]
+    }
+
+    fn apply(policy: &str, protected: &[&str], last_used: &[(&str, u64)]) -> Vec<String> {
+        let versions = get_versions();
+        let candidates: Vec<_> = versions
+            .iter()
+            .map(|v| PruneCandidate {
+                version: v,
+                last_used: last_used
+                    .iter()
+                    .find(|(lv, _)| *lv == v.to_string())
+                    .map(|(_, t)| *t),
+            })
+            .collect();
+        let protected: Vec<Version> = protected.iter().map(|v| v.parse().unwrap()).collect();
+
+        let policy: PrunePolicy = policy.parse().unwrap();
+        policy
+            .apply(&candidates, &protected, 100 * SECONDS_PER_DAY)
+            .iter()
+            .map(|(v, _)| v.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parse() {
+        let policy: PrunePolicy = "keep-latest-patch, keep-minors=3,unused-days=90"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            PrunePolicy {
+                keep_latest_patch: true,
+                keep_minors: Some(3),
+                drop_superseded_pre: false,
+                unused_days: Some(90),
+            },
+            policy
+        );
+        assert_eq!(
+            "keep-latest-patch,keep-minors=3,unused-days=90",
+            policy.to_string()
+        );
+
+        assert!("".parse::<PrunePolicy>().unwrap().is_empty());
+        assert!("keep-minors".parse::<PrunePolicy>().is_err());
+        assert!("keep-minors=a".parse::<PrunePolicy>().is_err());
+        assert!("foo".parse::<PrunePolicy>().is_err());
+    }
+
+    #[test]
+    fn keep_latest_patch() {
+        assert_eq!(
+            vec!["1.21.0", "1.21.12", "1.22.0"],
+            apply("keep-latest-patch", &[], &[])
+        );
+        assert_eq!(
+            vec!["1.21.0", "1.22.0"],
+            apply("keep-latest-patch", &["1.21.12"], &[])
+        );
+    }
+
+    #[test]
+    fn keep_minors() {
+        assert_eq!(
+            vec!["1.20.4", "1.21.0", "1.21.12", "1.21.13"],
+            apply("keep-minors=2", &[], &[])
+        );
+    }
+
+    #[test]
+    fn drop_superseded_pre() {
+        assert_eq!(vec!["1.22rc1"], apply("drop-superseded-pre", &[], &[]));
+    }
+
+    #[test]
+    fn protected() {
+        let policy = "keep-latest-patch,keep-minors=1,drop-superseded-pre,unused-days=30";
+        let protected = ["1.20.4", "1.21.0", "1.22rc1"];
+
+        let pruned = apply(policy, &protected, &[]);
+        assert_eq!(vec!["1.21.12", "1.21.13", "1.22.0", "1.22.1"], pruned);
+        assert!(protected.iter().all(|v| !pruned.contains(&v.to_string())));
+    }
+
+    #[test]
+    fn unused_days() {
+        assert_eq!(
+            vec!["1.20.4"],
+            apply(
+                "unused-days=30",
+                &[],
+                &[("1.20.4", 0), ("1.21.0", 80 * SECONDS_PER_DAY)]
+            )
+        );
+    }
+}
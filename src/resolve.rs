@@ -0,0 +1,183 @@
+use crate::{
+    env::{get_installed_versions, State},
+    versions::Version,
+};
+use anyhow::Result;
+use console::style;
+
+/// Version selectors which are resolved to the latest
+/// stable upstream release.
+pub const STABLE_SELECTORS: &[&str] = &["stable", "latest", "s"];
+
+/// Version selectors which are resolved to the latest
+/// upstream release, including pre-releases.
+pub const UNSTABLE_SELECTORS: &[&str] = &["unstable", "rc"];
+
+/// Resolves the given version input by replacing it with
+/// the target of the user defined alias with the same name.
+///
+/// If no alias exists with the given name, the input is
+/// returned as is.
+pub fn resolve_alias(input: &str) -> Result<String> {
+    let input = input.to_lowercase();
+    let state = State::load()?;
+
+    Ok(state.aliases.get(&input).cloned().unwrap_or(input))
+}
+
+/// Checks if the given name can be used as alias name.
+pub fn validate_alias_name(name: &str) -> Result<()> {
+    if STABLE_SELECTORS.contains(&name) || UNSTABLE_SELECTORS.contains(&name) {
+        anyhow::bail!("`{name}` is a reserved version selector and can not be used as alias.");
+    }
+
+    let version: Version = name
+        .parse()
+        .map_err(|_| anyhow::anyhow!("`{name}` is not a valid alias name."))?;
+
+    if !version.is_ident() {
+        anyhow::bail!("Alias names must not be versions.");
+    }
+
+    Ok(())
+}
+
+/// Checks and normalizes the given alias target, which must
+/// either be a built-in version selector or a version.
+///
+/// Versions may be suffixed with `.x` to select the latest
+/// release of a minor version *(`1.22.x` equals `1.22`)*.
+pub fn normalize_alias_target(target: &str) -> Result<String> {
+    let target = target.to_lowercase();
+
+    if STABLE_SELECTORS.contains(&target.as_str()) || UNSTABLE_SELECTORS.contains(&target.as_str())
+    {
+        return Ok(target);
+    }
+
+    let trimmed = target
+        .strip_suffix(".x")
+        .or_else(|| target.strip_suffix(".*"))
+        .unwrap_or(&target);
+
+    let version: Version = trimmed
+        .parse()
+        .map_err(|err| anyhow::anyhow!("`{target}` is not a valid alias target: {err}"))?;
+
+    Ok(version.to_string())
+}
+
+/// Returns the installed SDK version the given alias target
+/// points to, if any.
+pub fn get_alias_installed_target<'a>(
+    target: &str,
+    installed: &'a [Version],
+) -> Option<&'a Version> {
+    if STABLE_SELECTORS.contains(&target) {
+        return installed.iter().filter(|v| v.is_stable()).max();
+    }
+
+    if UNSTABLE_SELECTORS.contains(&target) {
+        return installed.iter().filter(|v| !v.is_ident()).max();
+    }
+
+    let target: Version = target.parse().ok()?;
+    installed
+        .iter()
+        .filter(|v| target.covers(v))
+        .filter(|v| v.is_stable() || !target.is_stable())
+        .max()
+}
+
+/// Returns the installed SDK matching the given version input,
+/// which may also be an alias or a version selector.
+///
+/// # Errors
+/// An error is returned if no or multiple installed SDKs
+/// match the input.
+pub fn find_installed_version(input: &str) -> Result<Version> {
+    find_version(input, &get_installed_versions()?)
+}
+
+/// Returns the version of the given `versions` matching the
+/// given version input *(see [`find_installed_version`])*.
+pub fn find_version(input: &str, versions: &[Version]) -> Result<Version> {
+    let target = resolve_alias(input)?;
+
+    let versions: Vec<_> = if STABLE_SELECTORS.contains(&target.as_str())
+        || UNSTABLE_SELECTORS.contains(&target.as_str())
+    {
+        get_alias_installed_target(&target, versions)
+            .into_iter()
+            .collect()
+    } else {
+        let target: Version = target.parse()?;
+        versions.iter().filter(|v| target.covers(v)).collect()
+    };
+
+    if versions.is_empty() {
+        anyhow::bail!("No SDK found matching the given version.");
+    }
+
+    if versions.len() > 1 {
+        let v: Vec<_> = versions.iter().map(|v| v.to_string()).collect();
+        anyhow::bail!(
+            "The given version matches multiple SDKs. Please supply a less ambigious version.\n\n{}\n{}",
+            style("Matching versions:").underlined(),
+            style(v.join("\n")).red()
+        );
+    }
+
+    Ok(versions[0].clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_alias_name() {
+        assert!(validate_alias_name("work").is_ok());
+        assert!(validate_alias_name("legacy-2").is_ok());
+        assert!(validate_alias_name("stable").is_err());
+        assert!(validate_alias_name("1.21").is_err());
+        assert!(validate_alias_name("foo/bar").is_err());
+    }
+
+    #[test]
+    fn test_normalize_alias_target() {
+        assert_eq!("1.22", normalize_alias_target("1.22.x").unwrap());
+        assert_eq!("1.22", normalize_alias_target("1.22.*").unwrap());
+        assert_eq!("1.21.5", normalize_alias_target("v1.21.5").unwrap());
+        assert_eq!("stable", normalize_alias_target("Stable").unwrap());
+        assert!(normalize_alias_target("1.2x").is_err());
+    }
+
+    #[test]
+    fn test_get_alias_installed_target() {
+        let installed: Vec<Version> = vec![
+            "1.21.4".parse().unwrap(),
+            "1.21.5".parse().unwrap(),
+            "1.22.1".parse().unwrap(),
+            "1.23rc1".parse().unwrap(),
+            "tip".parse().unwrap(),
+        ];
+
+        let exp: Version = "1.21.5".parse().unwrap();
+        assert_eq!(Some(&exp), get_alias_installed_target("1.21", &installed));
+
+        let exp: Version = "1.21.4".parse().unwrap();
+        assert_eq!(Some(&exp), get_alias_installed_target("1.21.4", &installed));
+
+        let exp: Version = "1.22.1".parse().unwrap();
+        assert_eq!(Some(&exp), get_alias_installed_target("stable", &installed));
+
+        let exp: Version = "1.23rc1".parse().unwrap();
+        assert_eq!(Some(&exp), get_alias_installed_target("rc", &installed));
+
+        let exp: Version = "tip".parse().unwrap();
+        assert_eq!(Some(&exp), get_alias_installed_target("tip", &installed));
+
+        assert_eq!(None, get_alias_installed_target("1.20", &installed));
+    }
+}
@@ -1,7 +1,7 @@
 pub mod errors;
 
 use self::errors::Error;
-use crate::env;
+use crate::{config::get_config, env};
 use std::{
     path::{Path, PathBuf},
     sync::OnceLock,
@@ -11,11 +11,15 @@ use whattheshell::Shell;
 /// A singleton OnceLock instance of the inferred current shell.
 static SHELL: OnceLock<Shell> = OnceLock::new();
 
-/// Returns the inferred current shell. If it has not been initialized already,
-/// it will be initialized. This might fail with a panic.
+/// Returns the configured or inferred current shell. If it has not been
+/// initialized already, it will be initialized. This might fail with a panic.
 pub fn get_shell() -> Shell {
     SHELL
-        .get_or_init(|| Shell::infer().expect("failed inferring current shell"))
+        .get_or_init(|| {
+            get_config()
+                .shell()
+                .unwrap_or_else(|| Shell::infer().expect("failed inferring current shell"))
+        })
         .clone()
 }
 
@@ -27,6 +31,9 @@ pub trait ShellEnv {
     /// Appends the given path string `new` to the passed `current` path
     /// concatenation string.
     fn append_to_path(&self, curr: &str, new: &str) -> Result<String, Error>;
+    /// Returns the separator used to join multiple paths which are
+    /// passed as `new` to [`ShellEnv::append_to_path`].
+    fn get_path_separator(&self) -> &'static str;
     /// Returns the directory of the users profile file.
     fn get_profile_dir(&self) -> Result<PathBuf, Error>;
     /// Takes a path reference and converts it to a shell-compatible string.
@@ -71,6 +78,21 @@ impl ShellEnv for Shell {
         }
     }
 
+    fn get_path_separator(&self) -> &'static str {
+        match self {
+            Self::Bash | Self::Sh | Self::Zsh => ":",
+            Self::Nushell => {
+                #[cfg(not(windows))]
+                return ":";
+
+                #[cfg(windows)]
+                return ";";
+            }
+            Self::Cmd | Self::PowerShell => ";",
+            Self::Fish => " ",
+        }
+    }
+
     fn get_profile_dir(&self) -> Result<PathBuf, Error> {
         let home = env::get_home_dir()?;
 
@@ -71,6 +71,26 @@ pub fn print_warning(v: &str) {
     );
 }
 
+/// Prints a note to stderr, so that it does not interfere
+/// with output which is evaluated by the shell.
+#[allow(unused_must_use)]
+pub fn eprint_note(v: &str) {
+    Term::stderr().clear_line();
+    eprintln!("{}", style(v).dim());
+}
+
+/// Prints a warning to stderr, so that it does not interfere
+/// with output which is evaluated by the shell.
+#[allow(unused_must_use)]
+pub fn eprint_warning(v: &str) {
+    Term::stderr().clear_line();
+    eprintln!(
+        "{} {}",
+        style("warning:").yellow().bold(),
+        style(v).yellow().bright()
+    );
+}
+
 pub fn accept(msg: &str, default_yes: bool) -> Result<bool> {
     let term = Term::stdout();
 
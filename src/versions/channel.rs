@@ -0,0 +1,133 @@
+use super::Version;
+use crate::resolve::{STABLE_SELECTORS, UNSTABLE_SELECTORS};
+use std::{fmt, str::FromStr};
+
+/// A release channel which the selected SDK can track.
+///
+/// When a channel is selected, `goup use` without arguments
+/// and `goup update` move the selection to the latest release
+/// which is contained in the channel.
+///
+/// # Examples
+/// ```
+/// let channel: Channel = "stable".parse().unwrap();
+/// let channel: Channel = "1.22".parse().unwrap();
+/// let channel: Channel = "1.22.x".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Channel {
+    /// The latest stable release.
+    Stable,
+    /// The latest release including pre-releases.
+    Unstable,
+    /// The latest stable patch release of a minor version.
+    Minor(Version),
+}
+
+impl Channel {
+    /// Returns true if the given version is part of the channel.
+    pub fn contains(&self, version: &Version) -> bool {
+        match self {
+            _ if version.is_ident() => false,
+            Self::Stable => version.is_stable(),
+            Self::Unstable => true,
+            Self::Minor(minor) => version.is_stable() && minor.covers(version),
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+
+        if STABLE_SELECTORS.contains(&s.as_str()) {
+            return Ok(Self::Stable);
+        }
+
+        if UNSTABLE_SELECTORS.contains(&s.as_str()) {
+            return Ok(Self::Unstable);
+        }
+
+        let trimmed = s
+            .strip_suffix(".x")
+            .or_else(|| s.strip_suffix(".*"))
+            .unwrap_or(&s);
+
+        let version: Version = trimmed.parse()?;
+        if version.is_ident()
+            || !version.is_stable()
+            || version.minor.is_none()
+            || version.patch.is_some()
+        {
+            anyhow::bail!("`{s}` is not a release channel");
+        }
+
+        Ok(Self::Minor(version))
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stable => write!(f, "stable"),
+            Self::Unstable => write!(f, "unstable"),
+            Self::Minor(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Channel::Stable, "stable".parse().unwrap());
+        assert_eq!(Channel::Stable, "Latest".parse().unwrap());
+        assert_eq!(Channel::Unstable, "rc".parse().unwrap());
+        assert_eq!(
+            Channel::Minor("1.22".parse().unwrap()),
+            "1.22".parse().unwrap()
+        );
+        assert_eq!(
+            Channel::Minor("1.22".parse().unwrap()),
+            "1.22.x".parse().unwrap()
+        );
+        assert_eq!("1.22", "v1.22.x".parse::<Channel>().unwrap().to_string());
+
+        assert!("1".parse::<Channel>().is_err());
+        assert!("1.22.4".parse::<Channel>().is_err());
+        assert!("1.22rc1".parse::<Channel>().is_err());
+        assert!("tip".parse::<Channel>().is_err());
+    }
+
+    fn resolve<'a>(channel: &Channel, versions: &'a [Version]) -> Option<&'a Version> {
+        versions.iter().rev().find(|v| channel.contains(v))
+    }
+
+    #[test]
+    fn contains() {
+        let versions: Vec<Version> = vec![
+            "1.21.4".parse().unwrap(),
+            "1.21.5".parse().unwrap(),
+            "1.22.0".parse().unwrap(),
+            "1.22.1".parse().unwrap(),
+            "1.23rc1".parse().unwrap(),
+        ];
+
+        let exp: Version = "1.22.1".parse().unwrap();
+        assert_eq!(Some(&exp), resolve(&Channel::Stable, &versions));
+
+        let exp: Version = "1.23rc1".parse().unwrap();
+        assert_eq!(Some(&exp), resolve(&Channel::Unstable, &versions));
+
+        let exp: Version = "1.21.5".parse().unwrap();
+        let channel: Channel = "1.21".parse().unwrap();
+        assert_eq!(Some(&exp), resolve(&channel, &versions));
+
+        let channel: Channel = "1.23".parse().unwrap();
+        assert_eq!(None, resolve(&channel, &versions));
+    }
+}
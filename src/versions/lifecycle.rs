@@ -0,0 +1,110 @@
+use super::{Version, VersionPart};
+
+/// The number of latest minor versions which are supported
+/// by the Go team with security fixes.
+pub const SUPPORTED_MINORS: usize = 2;
+
+/// Returns the minor versions *(like `1.22`)* which are currently
+/// supported, based on the given list of upstream versions
+/// *(sorted from oldest to latest)*.
+///
+/// Go supports each minor release until two newer minor
+/// releases have been published.
+pub fn get_supported_minors(upstream_versions: &[Version]) -> Vec<Version> {
+    let mut minors = get_released_minors(upstream_versions);
+    minors.reverse();
+    minors.truncate(SUPPORTED_MINORS);
+    minors
+}
+
+/// Returns the upstream release since which the given version is
+/// not supported anymore, or [`None`] if the version is still
+/// supported or is no upstream release.
+///
+/// This is the first release of the second minor version
+/// succeeding the minor version of `version`.
+///
+/// # Example
+/// ```
+/// let v: Version = "1.21.13".parse().unwrap();
+/// let since = get_unsupported_since(&upstream_versions, &v);
+/// // -> Some("1.23.0")
+/// ```
+pub fn get_unsupported_since<'a>(
+    upstream_versions: &'a [Version],
+    version: &Version,
+) -> Option<&'a Version> {
+    if version.is_ident() {
+        return None;
+    }
+
+    let minor = version.strip_after(VersionPart::Minor);
+    let successor = get_released_minors(upstream_versions)
+        .into_iter()
+        .filter(|m| m > &minor)
+        .nth(SUPPORTED_MINORS - 1)?;
+
+    upstream_versions
+        .iter()
+        .find(|v| v.is_stable() && successor.covers(v))
+}
+
+/// Returns the sorted minor versions of which at least
+/// one stable release has been published.
+fn get_released_minors(upstream_versions: &[Version]) -> Vec<Version> {
+    let mut minors: Vec<_> = upstream_versions
+        .iter()
+        .filter(|v| v.is_stable() && v.minor.is_some())
+        .map(|v| v.strip_after(VersionPart::Minor))
+        .collect();
+    minors.sort();
+    minors.dedup();
+    minors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_versions() -> Vec<Version> {
+        vec![
+            "1.20.14".parse().unwrap(),
+            "1.21.0".parse().unwrap(),
+            "1.21.13".parse().unwrap(),
+            "1.22.0".parse().unwrap(),
+            "1.22.6".parse().unwrap(),
+            "1.23rc1".parse().unwrap(),
+            "1.23.0".parse().unwrap(),
+            "1.23.1".parse().unwrap(),
+            "1.24rc1".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_get_supported_minors() {
+        let exp: Vec<Version> = vec!["1.23".parse().unwrap(), "1.22".parse().unwrap()];
+        assert_eq!(exp, get_supported_minors(&get_versions()));
+    }
+
+    #[test]
+    fn test_get_unsupported_since() {
+        let versions = get_versions();
+
+        let v = "1.21.13".parse().unwrap();
+        let exp: Version = "1.23.0".parse().unwrap();
+        assert_eq!(Some(&exp), get_unsupported_since(&versions, &v));
+
+        let v = "1.20.14".parse().unwrap();
+        let exp: Version = "1.22.0".parse().unwrap();
+        assert_eq!(Some(&exp), get_unsupported_since(&versions, &v));
+
+        let v = "1.22.0".parse().unwrap();
+        assert_eq!(None, get_unsupported_since(&versions, &v));
+
+        let v = "1.24rc1".parse().unwrap();
+        assert_eq!(None, get_unsupported_since(&versions, &v));
+
+        let v = Version::ident("tip");
+        assert_eq!(None, get_unsupported_since(&versions, &v));
+    }
+}
@@ -5,3 +5,12 @@ pub use version::*;
 
 mod util;
 pub use util::*;
+
+mod channel;
+pub use channel::*;
+
+mod lifecycle;
+pub use lifecycle::*;
+
+mod security;
+pub use security::*;
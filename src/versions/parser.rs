@@ -45,14 +45,31 @@ fn version_prefix(s: &str) -> IResult<&str, ()> {
         .or(Ok((s, ())))
 }
 
+fn is_ident(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
 /// Takes a Golang release version formatted string and
 /// parses it into a [`Version`].
+///
+/// Strings which do not start with a version number but
+/// are valid identifiers *(like `tip`)* are parsed into
+/// an identifier [`Version`].
 pub fn parse_version(s: &str) -> Result<Version> {
     let mut version = Version::default();
 
-    let (s, _) =
+    let (rest, _) =
         version_prefix(s).map_err(|e| anyhow::anyhow!("failed parsing version prefix: {e}"))?;
 
+    if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        anyhow::ensure!(is_ident(s), "invalid version or identifier: {s}");
+        return Ok(Version::ident(s));
+    }
+
+    let s = rest;
+
     let (s, major) = number(s).map_err(|e| anyhow::anyhow!("failed parsing major version: {e}"))?;
     version.major = major;
 
@@ -0,0 +1,173 @@
+use super::{Version, VersionPart};
+use crate::config::{get_config, RELEASE_HISTORY_URL};
+use anyhow::Result;
+use reqwest::{blocking::Client, header};
+use std::fs;
+
+/// Fetches the Go release history and returns all releases
+/// which include security fixes, sorted from oldest to latest.
+///
+/// The release history is fetched from
+/// [go.dev](https://go.dev/doc/devel/release) or from the URL or
+/// file path configured as `release_history_url` *(like a
+/// mirrored copy)*.
+pub fn get_security_releases() -> Result<Vec<Version>> {
+    let location = get_config().value(RELEASE_HISTORY_URL).unwrap_or_default();
+
+    let content = if location.starts_with("http://") || location.starts_with("https://") {
+        Client::builder()
+            .build()?
+            .get(&location)
+            .header(header::USER_AGENT, "goup")
+            .send()?
+            .error_for_status()?
+            .text()?
+    } else {
+        fs::read_to_string(&location)
+            .map_err(|err| anyhow::anyhow!("failed reading release history {location}: {err}"))?
+    };
+
+    let mut versions = parse_security_releases(&content);
+    versions.sort();
+
+    Ok(versions)
+}
+
+/// Returns all releases of the given list of security releases
+/// which are newer than `current` and part of the same minor
+/// version. These are the releases containing security fixes
+/// which are missing in `current`.
+pub fn get_missing_security_releases<'a>(
+    security_releases: &'a [Version],
+    current: &Version,
+) -> Vec<&'a Version> {
+    if current.is_ident() {
+        return vec![];
+    }
+
+    let minor = current.strip_after(VersionPart::Minor);
+    security_releases
+        .iter()
+        .filter(|v| *v > current && minor.covers(v))
+        .collect()
+}
+
+/// Parses the Go release history page and returns all releases
+/// which are stated to include security fixes.
+///
+/// Each release is introduced with a heading or paragraph like
+/// `go1.22.1 (released 2024-03-05) includes security fixes to ...`.
+/// The text up to the next release is considered to be the
+/// release description.
+pub fn parse_security_releases(html: &str) -> Vec<Version> {
+    let text = strip_tags(html);
+
+    let mut releases: Vec<(usize, Version)> = vec![];
+    for (i, _) in text.match_indices("go") {
+        let rest = &text[i + 2..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+            .unwrap_or(rest.len());
+
+        if !rest[end..].trim_start().starts_with("(released") {
+            continue;
+        }
+
+        if let Ok(v) = rest[..end].parse::<Version>() {
+            if !v.is_ident() {
+                releases.push((i, v));
+            }
+        }
+    }
+
+    releases
+        .iter()
+        .enumerate()
+        .filter(|(n, (start, _))| {
+            let end = releases.get(n + 1).map(|(i, _)| *i).unwrap_or(text.len());
+            text[*start..end].contains("security fix")
+        })
+        .map(|(_, (_, v))| v.clone())
+        .collect()
+}
+
+/// Removes all HTML tags from the given text and collapses
+/// all whitespace to single spaces.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HISTORY: &str = r#"
+<h2 id="go1.22.0">go1.22 (released 2024-02-06)</h2>
+<p>
+  Go 1.22 is a major release of Go.
+  Read the <a href="/doc/go1.22">Go 1.22 Release Notes</a> for more information.
+</p>
+<h3 id="go1.22.minor">Minor revisions</h3>
+<p>
+  go1.22.1 (released 2024-03-05) includes security fixes to the <code>crypto/x509</code>,
+  <code>html/template</code>, <code>net/http</code>, <code>net/http/cookiejar</code>, and
+  <code>net/mail</code> packages, as well as bug fixes to the compiler, the go command,
+  the runtime, the trace command, and the <code>go/types</code> and <code>net/http</code> packages.
+</p>
+<p>
+  go1.22.2 (released 2024-04-03) includes a security fix to the <code>net/http</code>
+  package, as well as bug fixes to the compiler.
+</p>
+<p>
+  go1.22.3 (released 2024-05-07) includes bug fixes to the compiler and the runtime.
+</p>
+<h2 id="go1.21.0">go1.21.0 (released 2023-08-08)</h2>
+<p>
+  go1.21.8 (released 2024-03-05) includes security fixes to the <code>crypto/x509</code> package.
+</p>
+"#;
+
+    #[test]
+    fn parse() {
+        let exp: Vec<Version> = vec![
+            "1.22.1".parse().unwrap(),
+            "1.22.2".parse().unwrap(),
+            "1.21.8".parse().unwrap(),
+        ];
+        assert_eq!(exp, parse_security_releases(HISTORY));
+    }
+
+    #[test]
+    fn missing_security_releases() {
+        let mut releases = parse_security_releases(HISTORY);
+        releases.sort();
+
+        let current = "1.22.1".parse().unwrap();
+        let exp: Version = "1.22.2".parse().unwrap();
+        assert_eq!(
+            vec![&exp],
+            get_missing_security_releases(&releases, &current)
+        );
+
+        let current = "1.22.2".parse().unwrap();
+        assert!(get_missing_security_releases(&releases, &current).is_empty());
+
+        let current = "1.20.4".parse().unwrap();
+        assert!(get_missing_security_releases(&releases, &current).is_empty());
+    }
+}
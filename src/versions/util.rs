@@ -1,16 +1,20 @@
 use super::{Version, VersionPart};
 use crate::{
     cmd::{self, exec},
-    warning,
+    config::{get_config, TAGS_ENDPOINT},
+    env::{ensure_dir, get_cache_dir},
+    policy::get_policy,
+    tui::eprint_warning,
 };
 use anyhow::Result;
 use reqwest::{blocking::Client, header};
 use serde::Deserialize;
-use std::str::FromStr;
+use std::{fs, io, str::FromStr, time::SystemTime};
 
-const GOLANG_REPO: &str = "https://github.com/golang/go.git";
-const GOLANG_TAGS_ENDPOINT: &str =
-    "https://api.github.com/repos/golang/go/git/matching-refs/tags/go";
+pub const GOLANG_REPO: &str = "https://github.com/golang/go.git";
+
+/// The file in the cache directory where upstream versions are cached.
+const UPSTREAM_VERSIONS_CACHE_FILE: &str = "upstream_versions.json";
 
 /// Queries all tags from the upstream
 /// [Go repository on GitHub](https://github.com/golang/go),
@@ -23,18 +27,67 @@ const GOLANG_TAGS_ENDPOINT: &str =
 /// `git ls-remote --tags` is used as fallback.
 pub fn get_upstream_versions() -> Result<Vec<Version>> {
     let mut tags = get_upstream_versions_api().or_else(|err| {
-        warning!(
+        // Printed to stderr to not break machine readable output.
+        eprint_warning(&format!(
             "Listing remote versions via GitHub API failed, falling back to using git ls-remote.\n\
             Error was: {err}"
-        );
+        ));
         get_upstream_versions_git()
     })?;
 
     tags.sort();
 
+    // Caching is best effort and must not fail listing versions.
+    _ = write_upstream_versions_cache(&tags);
+
     Ok(tags)
 }
 
+/// Returns the list of upstream versions *(see [`get_upstream_versions`])*
+/// as cached when they have last been fetched, or an empty list if they
+/// have never been fetched or the cache is older than the configured
+/// cache TTL *(one day by default)*.
+///
+/// No request is made, so the cache is only refreshed by commands
+/// fetching upstream versions, like `goup check`, `goup lsr` or
+/// `goup update`. This is designed to be used for supplementary
+/// information which must not slow down or break commands when offline,
+/// but must not be based on outdated versions either.
+pub fn get_cached_upstream_versions() -> Result<Vec<Version>> {
+    let file = get_cache_dir()?.join(UPSTREAM_VERSIONS_CACHE_FILE);
+
+    let outdated = fs::metadata(&file)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age >= get_config().cache_ttl());
+    if outdated {
+        return Ok(vec![]);
+    }
+
+    let content = match fs::read_to_string(&file) {
+        Ok(v) => v,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err.into()),
+    };
+
+    let versions: Vec<String> = serde_json::from_str(&content)?;
+    versions.iter().map(|v| v.parse()).collect()
+}
+
+fn write_upstream_versions_cache(versions: &[Version]) -> Result<()> {
+    let dir = get_cache_dir()?;
+    ensure_dir(&dir)?;
+
+    let versions: Vec<_> = versions.iter().map(ToString::to_string).collect();
+    fs::write(
+        dir.join(UPSTREAM_VERSIONS_CACHE_FILE),
+        serde_json::to_string(&versions)?,
+    )?;
+
+    Ok(())
+}
+
 /// Fetches a list of versions from the Go remote repository on
 /// GitHub using `git ls-remote --tags`.
 fn get_upstream_versions_git() -> Result<Vec<Version>> {
@@ -68,7 +121,7 @@ struct Ref {
 fn get_upstream_versions_api() -> Result<Vec<Version>> {
     let refs: Vec<Ref> = Client::builder()
         .build()?
-        .get(GOLANG_TAGS_ENDPOINT)
+        .get(get_config().value(TAGS_ENDPOINT).unwrap_or_default())
         .header(header::USER_AGENT, "goup")
         .send()?
         .error_for_status()?
@@ -86,14 +139,21 @@ fn get_upstream_versions_api() -> Result<Vec<Version>> {
 /// If `include_unstable` is passed as `true`, the latest version
 /// is returned including unstable versions.
 ///
+/// Versions which are not permitted by the version policy
+/// *(see [`get_policy`])* are skipped.
+///
 /// # Errors
 /// If no version has been found, an error of type [`anyhow::Error`]
 /// is returned with a message containing more details.
 pub fn get_latest_upstream_version(include_unstable: bool) -> Result<Version> {
-    get_upstream_versions()?
+    let versions = get_upstream_versions()?;
+    let candidates = versions
         .iter()
         .rev()
-        .find(|v| include_unstable || v.is_stable())
+        .filter(|v| include_unstable || v.is_stable());
+
+    get_policy()?
+        .select(candidates, &versions)?
         .cloned()
         .ok_or_else(|| anyhow::anyhow!("no stable version found"))
 }
@@ -106,15 +166,22 @@ pub fn get_latest_upstream_version(include_unstable: bool) -> Result<Version> {
 /// specified pre-release version is returned. Otherwise, only
 /// matching stable versions are returned.
 ///
+/// Versions which are not permitted by the version policy
+/// *(see [`get_policy`])* are skipped.
+///
 /// # Errors
 /// If no version has been found, an error of type [`anyhow::Error`]
 /// is returned with a message containing more details.
 pub fn find_upstream_version(s: &Version) -> Result<Version> {
-    get_upstream_versions()?
+    let versions = get_upstream_versions()?;
+    let candidates = versions
         .iter()
         .rev()
         .filter(|v| v.is_stable() || !s.is_stable())
-        .find(|v| s.covers(v))
+        .filter(|v| s.covers(v));
+
+    get_policy()?
+        .select(candidates, &versions)?
         .cloned()
         .ok_or_else(|| anyhow::anyhow!("no matching stable version found"))
 }
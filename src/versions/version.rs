@@ -6,20 +6,15 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum VersionState {
     Alpha(usize),
     Beta(usize),
     ReleaseCandidate(usize),
+    #[default]
     Release,
 }
 
-impl Default for VersionState {
-    fn default() -> Self {
-        Self::Release
-    }
-}
-
 impl fmt::Display for VersionState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -63,6 +58,11 @@ pub enum VersionPart {
 /// - `1.3beta1`
 /// - `1.4rc2`
 ///
+/// Toolchains which are not an upstream release (like SDKs
+/// built from source) are represented by an identifier
+/// instead *(`tip` or `release-branch.go1.24`, for example)*.
+/// In this case, all numeric parts are left empty.
+///
 /// # Examples
 /// Basic usage:
 /// ```
@@ -74,12 +74,35 @@ pub struct Version {
     pub minor: Option<usize>,
     pub patch: Option<usize>,
     pub pre: Option<VersionState>,
+    pub ident: Option<String>,
 }
 
 impl Version {
+    /// Creates a new identifier [`Version`] for a
+    /// non-release toolchain.
+    ///
+    /// # Example
+    /// ```
+    /// let version = Version::ident("tip");
+    /// assert!(version.is_ident());
+    /// ```
+    pub fn ident<S: Into<String>>(name: S) -> Self {
+        Self {
+            ident: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the version is an identifier
+    /// of a non-release toolchain.
+    pub fn is_ident(&self) -> bool {
+        self.ident.is_some()
+    }
+
     /// Returns true if the version defines a stable
     /// release (not suffixed with an `alpha`, `beta` or `rc`
-    /// version part).
+    /// version part). Identifier versions are never considered
+    /// stable.
     ///
     /// # Example
     /// ```
@@ -90,6 +113,10 @@ impl Version {
     /// assert!(!version.is_stable());
     /// ```
     pub fn is_stable(&self) -> bool {
+        if self.is_ident() {
+            return false;
+        }
+
         match &self.pre {
             None => true,
             Some(x) => matches!(x, VersionState::Release),
@@ -99,6 +126,9 @@ impl Version {
     /// Returns true if the current version covers
     /// the given `other` version.
     ///
+    /// Identifier versions only cover versions with
+    /// the same identifier.
+    ///
     /// # Example
     /// ```
     /// let a: Version = "1.2".parse().unwrap();
@@ -122,6 +152,10 @@ impl Version {
     /// assert!(!a.covers(&b));
     /// ```
     pub fn covers(&self, other: &Version) -> bool {
+        if self.is_ident() || other.is_ident() {
+            return self.ident == other.ident;
+        }
+
         if self.major != other.major {
             return false;
         }
@@ -195,6 +229,15 @@ impl Ord for Version {
             return Ordering::Equal;
         }
 
+        // Identifier versions are always ordered after
+        // release versions.
+        match (&self.ident, &other.ident) {
+            (Some(a), Some(b)) => return a.cmp(b),
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => {}
+        }
+
         match self.major.cmp(&other.major) {
             Ordering::Equal => {}
             ord => return ord,
@@ -233,6 +276,10 @@ impl PartialOrd for Version {
 
 impl Display for Version {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(ident) = &self.ident {
+            return f.write_str(ident);
+        }
+
         f.write_str(&self.major.to_string())?;
 
         if let Some(minor) = &self.minor {
@@ -334,11 +381,29 @@ mod test {
                 major: 1,
                 minor: Some(2),
                 patch: Some(345),
-                pre: Some(VersionState::Alpha(678))
+                pre: Some(VersionState::Alpha(678)),
+                ident: None,
             }
         );
     }
 
+    #[test]
+    fn parse_ident() {
+        assert_eq!(Version::from_str("tip").unwrap(), Version::ident("tip"));
+        assert_eq!(
+            Version::from_str("release-branch.go1.24").unwrap(),
+            Version::ident("release-branch.go1.24")
+        );
+        assert_eq!(
+            Version::from_str("commit-0a1b2c3d4e5f").unwrap(),
+            Version::ident("commit-0a1b2c3d4e5f")
+        );
+
+        assert!(Version::from_str("1.2foo").is_err());
+        assert!(Version::from_str("tip/foo").is_err());
+        assert!(Version::from_str("").is_err());
+    }
+
     #[test]
     fn ord() {
         assert!(Version::from_str("2").unwrap() > Version::from_str("1").unwrap());
@@ -350,6 +415,10 @@ mod test {
         assert!(Version::from_str("1.2rc1").unwrap() > Version::from_str("1.2beta1").unwrap());
         assert!(Version::from_str("1.2beta2").unwrap() > Version::from_str("1.2beta1").unwrap());
         assert!(Version::from_str("2").unwrap() > Version::from_str("1rc2").unwrap());
+        assert!(Version::from_str("tip").unwrap() > Version::from_str("1.22.1").unwrap());
+        assert!(
+            Version::from_str("tip").unwrap() > Version::from_str("release-branch.go1.24").unwrap()
+        );
     }
 
     #[test]
@@ -361,6 +430,7 @@ mod test {
         assert!(!Version::from_str("1alpha1").unwrap().is_stable());
         assert!(!Version::from_str("1.2beta2").unwrap().is_stable());
         assert!(!Version::from_str("1.2.3rc3").unwrap().is_stable());
+        assert!(!Version::from_str("tip").unwrap().is_stable());
     }
 
     #[test]
@@ -384,6 +454,14 @@ mod test {
         let a: Version = "1.3".parse().unwrap();
         let b: Version = "1.2.1".parse().unwrap();
         assert!(!a.covers(&b));
+
+        let a: Version = "tip".parse().unwrap();
+        let b: Version = "tip".parse().unwrap();
+        assert!(a.covers(&b));
+
+        let a: Version = "1".parse().unwrap();
+        let b: Version = "tip".parse().unwrap();
+        assert!(!a.covers(&b));
     }
 
     #[test]